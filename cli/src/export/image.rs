@@ -1,5 +1,3 @@
-use std::collections::hash_map::DefaultHasher;
-use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
 
 use ab_glyph::{FontRef, PxScale};
@@ -71,7 +69,7 @@ fn draw_courses(timetable: &Timetable, day_start: NaiveTime, img: &mut RgbImage)
     let height = duration.num_minutes() as f32 * MINUTE_HEIGHT;
     let rect = Rect::at(x as i32, y as i32).of_size(width, height as u32);
 
-    let background = color_hash(&course.code);
+    let background = Rgb(course.color());
     let average_color =
       background.0.iter().map(|&x| x as u16).sum::<u16>() / background.0.len() as u16;
     let foreground = match average_color as u8 {
@@ -169,14 +167,3 @@ fn get_font<'a>() -> FontRef<'a> {
   let font_data: &[u8] = include_bytes!("../../data/Helvetica.ttf");
   FontRef::try_from_slice(font_data).unwrap()
 }
-
-fn color_hash(course_code: &str) -> Rgb<u8> {
-  let mut hasher = DefaultHasher::new();
-  course_code.hash(&mut hasher);
-  let hash = hasher.finish();
-
-  let red = (hash & 0xFF) as u8;
-  let green = ((hash >> 8) & 0xFF) as u8;
-  let blue = ((hash >> 16) & 0xFF) as u8;
-  Rgb([red, green, blue])
-}
@@ -1,70 +1,111 @@
 use std::env;
 
-use itertools::Itertools;
-use permutator::CartesianProduct;
-use timetable_optimizer_lib::data::{Course, Subject, Timetable};
+use timetable_optimizer_lib::{
+  data::Subject,
+  filter,
+  generator::{self, TimetableGenerator},
+};
+
+use prompt::Input;
+use session::Session;
 
 mod export;
-mod filter;
+mod prompt;
 mod sample_data;
+mod session;
 mod setup;
-
-pub fn generate_timetables<'a>(subjects: &'a [Subject]) -> Vec<Timetable<'a>> {
-  let one_of_courses: Vec<Vec<&'a Course>> = subjects
-    .iter()
-    .flat_map(|subject| &subject.courses)
-    .map(|one_of_course| one_of_course.iter().collect::<Vec<&'a Course>>())
-    .collect();
-
-  let timetables: Vec<Timetable> = one_of_courses
-    .iter()
-    .map(|x| x.as_slice())
-    .collect::<Vec<&[&'a Course]>>()
-    .cart_prod()
-    .enumerate()
-    .map(|(i, cp)| {
-      Timetable::new(
-        i as u32,
-        cp.into_iter()
-          .copied()
-          .sorted_by_key(|course| course.occurrence.start_time)
-          .sorted_by_key(|course| course.occurrence.weekday as u8)
-          .collect::<Vec<&'a Course>>(),
-      )
-    })
-    // filter out overlapping courses
-    .filter(|timetable| {
-      timetable
-        .courses
-        .iter()
-        .chunk_by(|course| course.occurrence.weekday)
-        .into_iter()
-        .all(|(_, courses)| {
-          courses
-            .tuple_windows()
-            .all(|(current, next)| next.occurrence.start_time >= current.occurrence.end_time)
-        })
-    })
-    .collect();
-
-  timetables
-}
+mod stats;
 
 fn main() {
   let args: Vec<String> = env::args().collect();
+  let neptun_url = args
+    .iter()
+    .position(|arg| arg == "--neptun")
+    .and_then(|i| args.get(i + 1));
   let subjects: Vec<Subject> = if args.contains(&"--setup".to_string()) {
     setup::setup()
+  } else if let Some(neptun_url) = neptun_url {
+    setup::neptun_setup(neptun_url)
   } else {
     sample_data::get_subjects()
   };
 
-  let timetables = generate_timetables(&subjects);
-
+  let mut session = Session::default();
+  // the generator of the session's subjects and the indices of the timetables left by the filters,
+  // narrowed down as filters are added instead of generating all the timetables again
+  let mut current: Option<(TimetableGenerator, Vec<u64>)> = None;
   loop {
-    let filters = filter::prompt_filters();
-    // TODO: due to cloning, mutating won't persist (saving cached hash)
-    let mut filtered_timetables = filter::filter_timetables(timetables.clone(), filters);
+    let added_count = match prompt::prompt(&subjects) {
+      Input::Filters { specs, filters } => {
+        let added_count = filters.len();
+        session.add(specs, filters);
+        added_count
+      }
+      Input::Undo => match session.undo() {
+        Some(specs) => {
+          println!("Removed filters: {specs}");
+          current = None;
+          0
+        }
+        None => {
+          println!("No filters to undo");
+          continue;
+        }
+      },
+      Input::Show => {
+        println!("{}", session.show());
+        continue;
+      }
+      Input::Reset => {
+        session.reset();
+        current = None;
+        0
+      }
+      Input::Stats => {
+        stats::print_stats(&subjects, &session);
+        continue;
+      }
+      Input::Bottlenecks => {
+        stats::print_bottlenecks(&subjects, &session);
+        continue;
+      }
+    };
+
+    let filters = session.filters();
+    let added_filters = &filters[filters.len() - added_count..];
+    // the filters updating the courses change the combinations, so the timetables are generated again
+    let can_narrow_down = added_filters.iter().all(|filter| !filter.updates_courses());
+    let (generator, indices) = match current.take().filter(|_| can_narrow_down) {
+      Some((generator, indices)) => (generator, Some(indices)),
+      None => {
+        let subjects = filter::update_courses(&subjects, &filters);
+        (TimetableGenerator::new(&subjects), None)
+      }
+    };
+    let (timetables, new_filters) = match &indices {
+      Some(indices) => {
+        let timetables = indices
+          .iter()
+          .filter_map(|&index| generator.timetable_at(index))
+          .collect();
+        (timetables, added_filters)
+      }
+      None => match generator::generate_timetables(&generator) {
+        Ok(timetables) => (timetables, filters.as_slice()),
+        Err(e) => {
+          eprintln!("Error: {e}");
+          continue;
+        }
+      },
+    };
+
+    let mut filtered_timetables = filter::filter_timetables(timetables, new_filters);
     println!("Filtered timetables: {}", filtered_timetables.len());
     export::save_filtered(&mut filtered_timetables);
+    let indices = filtered_timetables
+      .iter()
+      .map(|timetable| timetable.id)
+      .collect();
+    current = Some((generator, indices));
   }
 }
@@ -0,0 +1,291 @@
+use std::{borrow::Cow, cell::RefCell, collections::HashMap};
+
+use itertools::Itertools;
+use rustyline::{
+  completion::{Completer, Pair},
+  highlight::Highlighter,
+  hint::{Hint, Hinter},
+  validate::Validator,
+  Context, Helper,
+};
+
+use crate::prompt::preset::Presets;
+use timetable_optimizer_lib::{
+  data::{CourseType, Subject},
+  filter, text,
+};
+
+const README: &str = include_str!("../../../README.md");
+const COMMANDS: &[&str] = &[
+  "undo",
+  "show",
+  "reset",
+  "stats",
+  "bottlenecks",
+  "presets",
+  "save",
+  "delete",
+];
+const WEEKDAYS: &[&str] = &["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+const RED: &str = "\x1b[31m";
+const GRAY: &str = "\x1b[90m";
+const RESET: &str = "\x1b[0m";
+
+/// Completes, hints and highlights filter specifications in the prompt.
+pub struct FilterHelper {
+  /// Filter names with their format, documented in the README.
+  formats: Vec<(&'static str, &'static str)>,
+  teachers: Vec<String>,
+  course_codes: Vec<String>,
+  languages: Vec<String>,
+  presets: Vec<String>,
+  /// Whether each spec of the last highlighted line is valid, so unchanged specs aren't checked again.
+  /// Only the last line is kept, so it doesn't grow with every line typed.
+  validity: RefCell<HashMap<String, bool>>,
+  /// The spec under the cursor when the line was last highlighted.
+  highlighted_spec: RefCell<String>,
+}
+
+impl FilterHelper {
+  pub fn new(subjects: &[Subject]) -> FilterHelper {
+    let courses = subjects
+      .iter()
+      .flat_map(|subject| &subject.courses)
+      .flatten()
+      .collect_vec();
+
+    FilterHelper {
+      formats: parse_formats(README),
+      teachers: courses
+        .iter()
+        .flat_map(|course| course.teachers())
+        .map(str::to_string)
+        .sorted()
+        .dedup()
+        .collect(),
+      course_codes: courses
+        .iter()
+        .map(|course| course.code.clone())
+        .sorted()
+        .dedup()
+        .collect(),
+      languages: courses
+        .iter()
+        .map(|course| course.language.clone())
+        .filter(|language| !language.is_empty())
+        .sorted()
+        .dedup()
+        .collect(),
+      presets: Presets::load().names(),
+      validity: RefCell::new(HashMap::new()),
+      highlighted_spec: RefCell::new(String::new()),
+    }
+  }
+
+  fn keywords(&self) -> impl Iterator<Item = String> + '_ {
+    self
+      .formats
+      .iter()
+      .map(|(name, _format)| format!("{name}="))
+      .chain(COMMANDS.iter().map(|command| command.to_string()))
+      .chain(self.presets.iter().map(|preset| format!("@{preset}")))
+  }
+
+  fn values(&self, key: &str) -> Vec<String> {
+    match key {
+      "exclude_teacher" | "include_teacher" => self.teachers.clone(),
+      "require_course" | "exclude_course" => self.course_codes.clone(),
+      "language" => self.languages.clone(),
+      "optional_course_type" => [
+        CourseType::Lecture,
+        CourseType::Laboratory,
+        CourseType::Practice,
+        CourseType::ELearning,
+      ]
+      .iter()
+      .map(CourseType::to_string)
+      .collect(),
+      "start_after" | "end_before" | "no_course_between" | "free_weekdays" | "availability" => {
+        WEEKDAYS.iter().map(|weekday| weekday.to_string()).collect()
+      }
+      _ => vec![],
+    }
+  }
+
+  fn format(&self, key: &str) -> Option<&'static str> {
+    self
+      .formats
+      .iter()
+      .find(|(name, _format)| *name == key)
+      .map(|(_name, format)| *format)
+  }
+
+  fn is_valid(&self, spec: &str, previous_validity: &HashMap<String, bool>) -> bool {
+    if let Some(preset) = spec.strip_prefix('@') {
+      return self.presets.iter().any(|name| name == preset);
+    }
+    match previous_validity.get(spec) {
+      Some(&is_valid) => is_valid,
+      None => filter::check_syntax(spec).is_ok(),
+    }
+  }
+}
+
+/// Collects the `Format:` line of every `### filter_name` section.
+fn parse_formats(readme: &'static str) -> Vec<(&'static str, &'static str)> {
+  let mut formats = vec![];
+  let mut current_filter = None;
+  for line in readme.lines() {
+    if let Some(name) = line.strip_prefix("### ") {
+      current_filter = Some(name.trim());
+    } else if let (Some(name), Some(format)) = (current_filter, line.strip_prefix("Format:")) {
+      formats.push((name, format.trim()));
+      current_filter = None;
+    }
+  }
+
+  formats
+}
+
+/// Start of the spec the cursor is in.
+fn current_spec_start(line: &str, pos: usize) -> usize {
+  match filter::spec_ranges(&line[..pos]).last() {
+    Some(range) if range.end == pos => range.start,
+    _ => pos,
+  }
+}
+
+fn quote_if_needed(value: &str) -> String {
+  if value.contains(' ') {
+    format!("\"{value}\"")
+  } else {
+    value.to_string()
+  }
+}
+
+impl Completer for FilterHelper {
+  type Candidate = Pair;
+
+  fn complete(
+    &self,
+    line: &str,
+    pos: usize,
+    _ctx: &Context<'_>,
+  ) -> rustyline::Result<(usize, Vec<Pair>)> {
+    let start = current_spec_start(line, pos);
+    let spec = &line[start..pos];
+
+    let candidates = match spec.split_once('=') {
+      None => self
+        .keywords()
+        .filter(|keyword| keyword.starts_with(spec))
+        .map(|keyword| Pair {
+          display: keyword.clone(),
+          replacement: keyword,
+        })
+        .collect(),
+      Some((key, value)) => {
+        let value = value.replace('"', "");
+        // per subject values are completed after the subject: `subject:value`
+        let (subject, value) = match value.split_once(':') {
+          Some((subject, value)) => (format!("{subject}:"), value),
+          None => (String::new(), value.as_str()),
+        };
+        self
+          .values(key)
+          .into_iter()
+          .filter(|candidate| text::normalize(candidate).starts_with(&text::normalize(value)))
+          .map(|candidate| Pair {
+            replacement: format!("{key}={}", quote_if_needed(&(subject.clone() + &candidate))),
+            display: candidate,
+          })
+          .collect()
+      }
+    };
+
+    Ok((start, candidates))
+  }
+}
+
+/// Only displayed, never inserted into the line.
+pub struct FormatHint(String);
+
+impl Hint for FormatHint {
+  fn display(&self) -> &str {
+    &self.0
+  }
+
+  fn completion(&self) -> Option<&str> {
+    None
+  }
+}
+
+impl Hinter for FilterHelper {
+  type Hint = FormatHint;
+
+  fn hint(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> Option<FormatHint> {
+    if pos < line.len() {
+      return None;
+    }
+
+    let start = current_spec_start(line, pos);
+    let key = line[start..pos].strip_suffix('=')?;
+    self
+      .format(key)
+      .map(|format| FormatHint(format!("  Format: {format}")))
+  }
+}
+
+impl Highlighter for FilterHelper {
+  fn highlight<'l>(&self, line: &'l str, _pos: usize) -> Cow<'l, str> {
+    let is_command = line
+      .split_whitespace()
+      .next()
+      .is_some_and(|word| COMMANDS.contains(&word));
+    if is_command {
+      return Cow::Borrowed(line);
+    }
+
+    let previous_validity = self.validity.take();
+    let mut validity = self.validity.borrow_mut();
+    let mut highlighted = String::new();
+    let mut last_end = 0;
+    for range in filter::spec_ranges(line) {
+      highlighted.push_str(&line[last_end..range.start]);
+      let spec = &line[range.clone()];
+      let unquoted_spec = spec.replace('"', "");
+      let is_valid = self.is_valid(&unquoted_spec, &previous_validity);
+      validity.insert(unquoted_spec, is_valid);
+      if is_valid {
+        highlighted.push_str(spec);
+      } else {
+        highlighted.push_str(&format!("{RED}{spec}{RESET}"));
+      }
+      last_end = range.end;
+    }
+    highlighted.push_str(&line[last_end..]);
+
+    Cow::Owned(highlighted)
+  }
+
+  fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
+    Cow::Owned(format!("{GRAY}{hint}{RESET}"))
+  }
+
+  fn highlight_char(&self, line: &str, pos: usize, forced: bool) -> bool {
+    let spec = filter::spec_ranges(line)
+      .into_iter()
+      .find(|range| range.contains(&pos) || range.end == pos)
+      .map_or("", |range| &line[range]);
+    let changed = *self.highlighted_spec.borrow() != spec;
+    if changed {
+      *self.highlighted_spec.borrow_mut() = spec.to_string();
+    }
+
+    forced || changed
+  }
+}
+
+impl Validator for FilterHelper {}
+
+impl Helper for FilterHelper {}
@@ -0,0 +1,71 @@
+use rustyline::{history::DefaultHistory, Editor};
+
+use helper::FilterHelper;
+use preset::Presets;
+use timetable_optimizer_lib::{
+  data::Subject,
+  filter::{self, Filter},
+};
+
+mod helper;
+mod preset;
+
+pub enum Input {
+  Filters {
+    specs: String,
+    filters: Vec<Box<dyn Filter>>,
+  },
+  Undo,
+  Show,
+  Reset,
+  Stats,
+  Bottlenecks,
+}
+
+pub fn prompt(subjects: &[Subject]) -> Input {
+  let mut rl = Editor::<FilterHelper, DefaultHistory>::new().unwrap();
+  rl.set_helper(Some(FilterHelper::new(subjects)));
+  let hist_file = "out/history.txt";
+  rl.load_history(hist_file).ok();
+  let specs = rl.readline("Enter filter: ").unwrap();
+  rl.add_history_entry(specs.as_str()).unwrap();
+  rl.save_history(hist_file).unwrap();
+
+  match specs.trim() {
+    "undo" => return Input::Undo,
+    "show" => return Input::Show,
+    "reset" => return Input::Reset,
+    "stats" => return Input::Stats,
+    "bottlenecks" => return Input::Bottlenecks,
+    _ => {}
+  }
+
+  let mut presets = Presets::load();
+  if let Some(result) = presets.try_command(&specs) {
+    match result {
+      Ok(message) => println!("{message}"),
+      Err(e) => eprintln!("Error: {e}"),
+    }
+    return prompt(subjects);
+  }
+
+  let filters_parsed = presets
+    .expand(filter::split_specs(&specs))
+    .and_then(|specs| {
+      specs
+        .iter()
+        .map(|spec| filter::parse_filter(spec))
+        .collect::<Result<_, _>>()
+    });
+
+  match filters_parsed {
+    Ok(filters) => Input::Filters {
+      specs: specs.trim().to_string(),
+      filters,
+    },
+    Err(e) => {
+      eprintln!("Error parsing filter: {e}");
+      prompt(subjects)
+    }
+  }
+}
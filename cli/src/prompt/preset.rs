@@ -0,0 +1,134 @@
+use std::{collections::BTreeMap, fs};
+
+use itertools::Itertools;
+
+use timetable_optimizer_lib::filter;
+
+const PRESETS_FILE: &str = "out/presets.txt";
+
+/// Named filter specifications, stored as `@name = specs` lines.
+pub struct Presets(BTreeMap<String, String>);
+
+impl Presets {
+  pub fn load() -> Presets {
+    let content = fs::read_to_string(PRESETS_FILE).unwrap_or_default();
+    let presets = content
+      .lines()
+      .filter_map(|line| line.split_once('='))
+      .filter_map(|(name, specs)| {
+        let name = name.trim().strip_prefix('@')?;
+        Some((name.to_string(), specs.trim().to_string()))
+      })
+      .collect();
+    Presets(presets)
+  }
+
+  pub fn names(&self) -> Vec<String> {
+    self.0.keys().cloned().collect()
+  }
+
+  fn save(&self) {
+    let content = self
+      .0
+      .iter()
+      .map(|(name, specs)| format!("@{name} = {specs}\n"))
+      .join("");
+    fs::create_dir_all("out").ok();
+    fs::write(PRESETS_FILE, content).unwrap();
+  }
+
+  /// Replaces `@name` specs with the specs of the preset, presets can refer to other presets.
+  pub fn expand(&self, specs: Vec<String>) -> Result<Vec<String>, String> {
+    self.expand_with_parents(specs, &mut vec![])
+  }
+
+  fn expand_with_parents(
+    &self,
+    specs: Vec<String>,
+    parents: &mut Vec<String>,
+  ) -> Result<Vec<String>, String> {
+    let mut expanded = vec![];
+    for spec in specs {
+      let Some(name) = spec.strip_prefix('@') else {
+        expanded.push(spec);
+        continue;
+      };
+      let preset = self.0.get(name).ok_or(format!("Unknown preset: @{name}"))?;
+      if parents.iter().any(|parent| parent == name) {
+        return Err(format!("Preset refers to itself: @{name}"));
+      }
+
+      parents.push(name.to_string());
+      expanded.extend(self.expand_with_parents(filter::split_specs(preset), parents)?);
+      parents.pop();
+    }
+
+    Ok(expanded)
+  }
+
+  /// Handles the `presets`, `save @name specs` and `delete @name` commands,
+  /// returns `None` when the input is not a preset command.
+  pub fn try_command(&mut self, input: &str) -> Option<Result<String, String>> {
+    let input = input.trim();
+    let (command, args) = input.split_once(' ').unwrap_or((input, ""));
+    match command {
+      "presets" => Some(Ok(self.list())),
+      "save" => Some(self.save_preset(args)),
+      "delete" => Some(self.delete_preset(args)),
+      _ => None,
+    }
+  }
+
+  fn list(&self) -> String {
+    if self.0.is_empty() {
+      return "No presets saved".to_string();
+    }
+
+    self
+      .0
+      .iter()
+      .map(|(name, specs)| format!("@{name} = {specs}"))
+      .join("\n")
+  }
+
+  fn save_preset(&mut self, args: &str) -> Result<String, String> {
+    let (name, specs) = args.trim().split_once(' ').unwrap_or((args.trim(), ""));
+    let name = parse_name(name)?;
+    let specs = specs.trim();
+    if specs.is_empty() {
+      return Err(format!("Missing filters for preset: @{name}"));
+    }
+
+    // a preset has to be usable, so check it before saving
+    let mut presets = Presets(self.0.clone());
+    presets.0.insert(name.to_string(), specs.to_string());
+    presets
+      .expand(filter::split_specs(specs))?
+      .iter()
+      .map(|spec| filter::parse_filter(spec))
+      .collect::<Result<Vec<_>, _>>()?;
+
+    *self = presets;
+    self.save();
+    Ok(format!("Saved preset: @{name}"))
+  }
+
+  fn delete_preset(&mut self, args: &str) -> Result<String, String> {
+    let name = parse_name(args.trim())?;
+    self
+      .0
+      .remove(name)
+      .ok_or(format!("Unknown preset: @{name}"))?;
+    self.save();
+    Ok(format!("Deleted preset: @{name}"))
+  }
+}
+
+fn parse_name(name: &str) -> Result<&str, String> {
+  name
+    .strip_prefix('@')
+    .filter(|name| !name.is_empty() && !name.contains(['=', '"']))
+    .ok_or(format!(
+      "Invalid preset name, it should look like @name: {name}"
+    ))
+}
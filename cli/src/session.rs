@@ -0,0 +1,60 @@
+use itertools::Itertools;
+
+use timetable_optimizer_lib::filter::Filter;
+
+/// The filters added step by step, each step narrows down the timetables of the previous one.
+#[derive(Default)]
+pub struct Session {
+  steps: Vec<Step>,
+}
+
+struct Step {
+  specs: String,
+  filters: Vec<Box<dyn Filter>>,
+}
+
+impl Session {
+  pub fn add(&mut self, specs: String, filters: Vec<Box<dyn Filter>>) {
+    if !filters.is_empty() {
+      self.steps.push(Step { specs, filters });
+    }
+  }
+
+  pub fn undo(&mut self) -> Option<String> {
+    self.steps.pop().map(|step| step.specs)
+  }
+
+  pub fn reset(&mut self) {
+    self.steps.clear();
+  }
+
+  pub fn show(&self) -> String {
+    if self.steps.is_empty() {
+      return "No active filters".to_string();
+    }
+
+    self
+      .steps
+      .iter()
+      .enumerate()
+      .map(|(i, step)| format!("{}. {}", i + 1, step.specs))
+      .join("\n")
+  }
+
+  /// The specs of each step and how many filters they were parsed into.
+  pub fn steps(&self) -> impl Iterator<Item = (&str, usize)> {
+    self
+      .steps
+      .iter()
+      .map(|step| (step.specs.as_str(), step.filters.len()))
+  }
+
+  pub fn filters(&self) -> Vec<&dyn Filter> {
+    self
+      .steps
+      .iter()
+      .flat_map(|step| &step.filters)
+      .map(|filter| filter.as_ref())
+      .collect()
+  }
+}
@@ -5,7 +5,7 @@ use std::{
 
 use calamine::Xlsx;
 
-use timetable_optimizer_lib::{data::Subject, excel_parser};
+use timetable_optimizer_lib::{data::Subject, excel_parser, neptun::NeptunClient};
 
 pub fn setup() -> Vec<Subject> {
   println!("Export the courses from each subject from Neptun to an Excel file then drag and drop those file here");
@@ -41,7 +41,60 @@ fn read_subject() -> Option<Subject> {
   };
 
   let path = Path::new(path_str);
-  let file_name = path.file_name().unwrap().to_str().unwrap().to_string();
-  let subject = excel_parser::parse_subject(file_name, &mut excel);
-  Some(subject)
+  let file_name = path.file_name().unwrap().to_str().unwrap();
+  let subject_name = excel_parser::subject_name(file_name, &mut excel);
+  match excel_parser::parse_subject(subject_name, &mut excel) {
+    Ok(subject) => Some(subject),
+    Err(err) => {
+      eprintln!("Failed to parse excel file: {}", err);
+      read_subject()
+    }
+  }
+}
+
+/// Logs into the Neptun at the base URL and fetches the registered subjects,
+/// an empty Neptun code gives up logging in.
+pub fn neptun_setup(base_url: &str) -> Vec<Subject> {
+  let client = loop {
+    let user_login = read_line("Neptun code: ");
+    if user_login.is_empty() {
+      return vec![];
+    }
+    let password = rpassword::prompt_password("Password: ").unwrap();
+    match NeptunClient::login(base_url, &user_login, &password) {
+      Ok(client) => break client,
+      Err(err) => eprintln!("Failed to log in: {err}"),
+    }
+  };
+
+  let registered_subjects = match client.registered_subjects() {
+    Ok(registered_subjects) => registered_subjects,
+    Err(err) => {
+      eprintln!("Failed to list the registered subjects: {err}");
+      return vec![];
+    }
+  };
+  let subjects = registered_subjects
+    .iter()
+    .filter_map(|registered| match client.subject(registered) {
+      Ok(subject) => Some(subject),
+      Err(err) => {
+        eprintln!("Failed to fetch the courses: {err}");
+        None
+      }
+    })
+    .collect::<Vec<_>>();
+
+  let subject_count = subjects.len();
+  println!("Fetched {subject_count} subjects from Neptun");
+
+  subjects
+}
+
+fn read_line(prompt: &str) -> String {
+  print!("{prompt}");
+  io::stdout().flush().unwrap();
+  let mut line = String::new();
+  io::stdin().read_line(&mut line).unwrap();
+  line.trim().to_string()
 }
@@ -0,0 +1,129 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveTime;
+use itertools::Itertools;
+use timetable_optimizer_lib::{
+  bottleneck,
+  data::Subject,
+  filter,
+  generator::{self, TimetableGenerator},
+  stats,
+};
+
+use crate::session::Session;
+
+const BAR_WIDTH: usize = 40;
+
+/// Prints the statistics of the timetables left by the filters of the session.
+pub fn print_stats(subjects: &[Subject], session: &Session) {
+  let filters = session.filters();
+  let course_funnel = stats::course_filter_funnel(subjects, &filters);
+  println!(
+    "Timetables without clashes: {}",
+    TimetableGenerator::new(subjects).timetable_count()
+  );
+  let subjects = filter::update_courses(subjects, &filters);
+  let generator = TimetableGenerator::new(&subjects);
+  let timetables = match generator::generate_timetables(&generator) {
+    Ok(timetables) => timetables,
+    Err(e) => {
+      eprintln!("Error: {e}");
+      return;
+    }
+  };
+
+  // the steps only updating the courses take effect first, before the timetables are generated
+  let funnel = stats::filter_funnel(&timetables, &filters);
+  let steps = session
+    .steps()
+    .scan(0, |end, (specs, count)| {
+      *end += count;
+      Some((specs, *end - count..*end))
+    })
+    .collect_vec();
+  let (course_steps, timetable_steps): (Vec<_>, Vec<_>) = steps.iter().partition(|(_, range)| {
+    range
+      .clone()
+      .all(|i| course_funnel.iter().any(|&(filter, _)| filter == i))
+  });
+  let course_counts = course_steps.iter().map(|(specs, range)| {
+    let (_, count) = course_funnel
+      .iter()
+      .find(|&&(filter, _)| filter == range.end - 1)
+      .unwrap();
+    (specs, count.to_string())
+  });
+  let timetable_counts = timetable_steps
+    .iter()
+    .map(|(specs, range)| (specs, funnel[range.end - 1].to_string()));
+  for (i, (specs, count)) in course_counts.chain(timetable_counts).enumerate() {
+    println!("{}. {specs}: {count}", i + 1);
+  }
+
+  let filtered_timetables = filter::filter_timetables(timetables, &filters);
+  let stats = stats::timetable_stats(&filtered_timetables);
+  println!("Filtered timetables: {}", stats.count);
+  if stats.count == 0 {
+    return;
+  }
+
+  let format_time = |time: &NaiveTime| time.format("%H:%M").to_string();
+  print_histogram("Free workdays", &stats.free_workdays, usize::to_string);
+  print_histogram("Earliest start", &stats.earliest_start, format_time);
+  print_histogram("Latest end", &stats.latest_end, format_time);
+  print_histogram("Total gap hours", &stats.total_gap_hours, i64::to_string);
+
+  println!("Course frequency:");
+  for (subject_name, code) in stats.course_frequency.keys() {
+    println!(
+      "  {code} ({subject_name}): {:.0}%",
+      stats.course_percentage(subject_name, code)
+    );
+  }
+}
+
+/// Prints the groups that can't be in a timetable together,
+/// and the left out courses that would add the most timetables.
+pub fn print_bottlenecks(subjects: &[Subject], session: &Session) {
+  let filters = session.filters();
+  let subjects = filter::update_courses(subjects, &filters);
+
+  let conflicts = bottleneck::conflicting_groups(&subjects);
+  if conflicts.is_incomplete {
+    println!(
+      "No conflicting groups found, only up to {} groups were searched",
+      bottleneck::MAX_CONFLICT_SIZE
+    );
+  } else if conflicts.sets.is_empty() {
+    println!("No conflicting groups");
+  } else {
+    println!("Conflicting groups:");
+    for conflict in conflicts.sets {
+      println!("  {}", conflict.iter().join(", "));
+    }
+  }
+
+  let unlocks = bottleneck::unlocking_courses(&subjects);
+  if !unlocks.is_empty() {
+    println!("Courses adding timetables if re-enabled:");
+    for unlock in unlocks {
+      println!(
+        "  {} ({}): {} timetables",
+        unlock.code, unlock.subject_name, unlock.timetable_count
+      );
+    }
+  }
+}
+
+fn print_histogram<K>(
+  title: &str,
+  histogram: &BTreeMap<K, usize>,
+  format_key: impl Fn(&K) -> String,
+) {
+  println!("{title}:");
+  let max = histogram.values().max().copied().unwrap_or(1);
+  for (key, count) in histogram {
+    let bar = "#".repeat((count * BAR_WIDTH).div_ceil(max));
+    println!("  {:>5} {count:>6} {bar}", format_key(key));
+  }
+}
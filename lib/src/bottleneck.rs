@@ -0,0 +1,317 @@
+use std::fmt::{self, Display, Formatter};
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  data::{CourseType, OneOfCourse, Subject},
+  generator::{TimetableCount, TimetableGenerator},
+};
+
+/// Sets of up to this many groups are searched for conflicts, as the number of sets grows quickly.
+/// Larger conflicts are only found by narrowing down all the groups, when they have no timetables.
+pub const MAX_CONFLICT_SIZE: usize = 3;
+
+/// A group of courses of a subject, one of them has to be in the timetable.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct CourseGroup {
+  pub subject_name: String,
+  /// Index of the group among the groups of the subject.
+  pub index: usize,
+  pub course_type: CourseType,
+}
+
+impl Display for CourseGroup {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(f, "{} {} group", self.subject_name, self.course_type)
+  }
+}
+
+/// Sets of groups without a single combination that doesn't clash,
+/// none of the sets contain an other one.
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct Conflicts {
+  pub sets: Vec<Vec<CourseGroup>>,
+  /// There may be no timetables, but the timetables took too long to count to find out why,
+  /// only the sets of up to `MAX_CONFLICT_SIZE` groups were searched.
+  pub is_incomplete: bool,
+}
+
+/// A course left out of the timetables, and the count of the timetables with it re-enabled.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Unlock {
+  pub subject_name: String,
+  pub code: String,
+  pub timetable_count: TimetableCount,
+}
+
+pub fn conflicting_groups(subjects: &[Subject]) -> Conflicts {
+  let total = TimetableGenerator::new(subjects).timetable_count();
+  // a sampled timetable proves there are timetables, so no set of groups conflicts
+  if total.value() > 0 {
+    return Conflicts::default();
+  }
+
+  // the same groups as the generator's, a group hidden entirely conflicts on its own
+  let groups = subjects
+    .iter()
+    .flat_map(|subject| {
+      subject
+        .courses
+        .iter()
+        .enumerate()
+        .map(move |(index, one_of_course)| (subject, index, one_of_course))
+    })
+    .filter(|(_, _, one_of_course)| one_of_course.iter().any(|course| !course.is_deleted))
+    .collect_vec();
+  let has_no_timetables = |set: &[usize]| {
+    let set_subjects = set
+      .iter()
+      .map(|&group| {
+        let (subject, _, one_of_course) = groups[group];
+        single_group_subject(subject, one_of_course)
+      })
+      .collect_vec();
+    TimetableGenerator::new(&set_subjects).timetable_count() == TimetableCount::Exact(0)
+  };
+
+  let mut conflicts: Vec<Vec<usize>> = vec![];
+  for size in 1..=MAX_CONFLICT_SIZE {
+    for set in (0..groups.len()).combinations(size) {
+      let contains_conflict = conflicts
+        .iter()
+        .any(|conflict| conflict.iter().all(|group| set.contains(group)));
+      if !contains_conflict && has_no_timetables(&set) {
+        conflicts.push(set);
+      }
+    }
+  }
+
+  let mut is_incomplete = false;
+  if conflicts.is_empty() {
+    if total == TimetableCount::Exact(0) {
+      conflicts.push(narrow_down(groups.len(), has_no_timetables));
+    } else {
+      is_incomplete = true;
+    }
+  }
+
+  let sets = conflicts
+    .into_iter()
+    .map(|set| {
+      set
+        .into_iter()
+        .map(|group| {
+          let (subject, index, one_of_course) = groups[group];
+          CourseGroup {
+            subject_name: subject.name.clone(),
+            index,
+            course_type: one_of_course[0].course_type,
+          }
+        })
+        .collect()
+    })
+    .collect();
+  Conflicts {
+    sets,
+    is_incomplete,
+  }
+}
+
+/// Leaves out the groups one by one while the rest still has no timetables,
+/// so every group of the remaining set is part of the conflict, as far as they could be counted.
+fn narrow_down(group_count: usize, has_no_timetables: impl Fn(&[usize]) -> bool) -> Vec<usize> {
+  let mut set = (0..group_count).collect_vec();
+  let mut i = 0;
+  while i < set.len() {
+    let mut smaller = set.clone();
+    smaller.remove(i);
+    if has_no_timetables(&smaller) {
+      set = smaller;
+    } else {
+      i += 1;
+    }
+  }
+
+  set
+}
+
+/// The hidden and deleted courses that would add timetables if they were re-enabled,
+/// the ones adding the most first.
+pub fn unlocking_courses(subjects: &[Subject]) -> Vec<Unlock> {
+  let current_count = TimetableGenerator::new(subjects).timetable_count();
+  let ignored_courses = subjects.iter().flat_map(|subject| {
+    subject
+      .courses
+      .iter()
+      .flatten()
+      .filter(|course| course.is_ignored())
+      .map(move |course| (&subject.name, &course.code))
+  });
+
+  ignored_courses
+    .filter_map(|(subject_name, code)| {
+      // course codes are only unique within a subject
+      let mut subjects = subjects.to_vec();
+      subjects
+        .iter_mut()
+        .filter(|subject| &subject.name == subject_name)
+        .flat_map(|subject| &mut subject.courses)
+        .flatten()
+        .filter(|course| &course.code == code)
+        .for_each(|course| {
+          course.is_deleted = false;
+          course.is_hidden_by_user = false;
+        });
+      let timetable_count = TimetableGenerator::new(&subjects).timetable_count();
+      (timetable_count.value() > current_count.value()).then(|| Unlock {
+        subject_name: subject_name.clone(),
+        code: code.clone(),
+        timetable_count,
+      })
+    })
+    .sorted_by_key(|unlock| std::cmp::Reverse(unlock.timetable_count.value()))
+    .collect()
+}
+
+fn single_group_subject(subject: &Subject, one_of_course: &OneOfCourse) -> Subject {
+  Subject {
+    name: subject.name.clone(),
+    courses: vec![one_of_course.clone()],
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use chrono::{NaiveTime, Weekday};
+
+  use super::{conflicting_groups, unlocking_courses, CourseGroup};
+  use crate::{
+    data::{Course, CourseType, Enrollment, Occurrence, Subject},
+    generator::TimetableCount,
+  };
+
+  fn course(code: &str, weekday: Weekday, start: u32, end: u32) -> Course {
+    Course::new(
+      String::new(),
+      code.to_string(),
+      CourseType::Practice,
+      Enrollment::default(),
+      String::new(),
+      String::new(),
+      String::new(),
+      String::new(),
+      String::new(),
+      String::new(),
+      Occurrence {
+        weeks: None,
+        weekday,
+        start_time: NaiveTime::from_hms_opt(start, 0, 0).unwrap(),
+        end_time: NaiveTime::from_hms_opt(end, 0, 0).unwrap(),
+      },
+    )
+  }
+
+  fn hidden(mut course: Course) -> Course {
+    course.is_hidden_by_user = true;
+    course
+  }
+
+  fn subject(name: &str, courses: Vec<Course>) -> Subject {
+    Subject {
+      name: name.to_string(),
+      courses: vec![courses],
+    }
+  }
+
+  fn group(subject_name: &str) -> CourseGroup {
+    CourseGroup {
+      subject_name: subject_name.to_string(),
+      index: 0,
+      course_type: CourseType::Practice,
+    }
+  }
+
+  /// Subjects sharing the same times, any of them fit but not all of them.
+  fn same_times(names: &[&str], hours: &[u32]) -> Vec<Subject> {
+    names
+      .iter()
+      .map(|name| {
+        let courses = hours
+          .iter()
+          .map(|&hour| course(&format!("GY_{hour}"), Weekday::Mon, hour, hour + 1))
+          .collect();
+        subject(name, courses)
+      })
+      .collect()
+  }
+
+  #[test]
+  fn two_clashing_groups_conflict() {
+    let subjects = [
+      subject("Physics", vec![course("GY_01", Weekday::Mon, 8, 10)]),
+      subject("Chemistry", vec![course("GY_01", Weekday::Mon, 9, 11)]),
+      subject("Biology", vec![course("GY_01", Weekday::Tue, 8, 10)]),
+    ];
+    let conflicts = conflicting_groups(&subjects);
+    assert_eq!(conflicts.sets, [[group("Physics"), group("Chemistry")]]);
+    assert!(!conflicts.is_incomplete);
+  }
+
+  #[test]
+  fn three_groups_conflict_when_any_two_fit() {
+    let subjects = same_times(&["Physics", "Chemistry", "Biology"], &[8, 10]);
+    let conflicts = conflicting_groups(&subjects);
+    assert_eq!(
+      conflicts.sets,
+      [[group("Physics"), group("Chemistry"), group("Biology")]]
+    );
+  }
+
+  #[test]
+  fn larger_conflicts_are_narrowed_down() {
+    let mut subjects = same_times(
+      &["Physics", "Chemistry", "Biology", "History"],
+      &[8, 10, 12],
+    );
+    subjects.push(subject("Art", vec![course("GY_01", Weekday::Tue, 8, 10)]));
+    let conflicts = conflicting_groups(&subjects);
+    assert_eq!(
+      conflicts.sets,
+      [[
+        group("Physics"),
+        group("Chemistry"),
+        group("Biology"),
+        group("History")
+      ]]
+    );
+    assert!(!conflicts.is_incomplete);
+  }
+
+  #[test]
+  fn hidden_course_unlocks_timetables() {
+    let subjects = [
+      subject("Physics", vec![course("GY_01", Weekday::Mon, 8, 10)]),
+      subject(
+        "Chemistry",
+        vec![
+          course("GY_01", Weekday::Mon, 9, 11),
+          hidden(course("GY_02", Weekday::Tue, 8, 10)),
+        ],
+      ),
+      // the same code in an other subject is an other course
+      subject(
+        "Biology",
+        vec![
+          course("GY_01", Weekday::Wed, 8, 10),
+          hidden(course("GY_02", Weekday::Wed, 10, 12)),
+        ],
+      ),
+    ];
+    let unlocks = unlocking_courses(&subjects);
+    assert_eq!(unlocks.len(), 1);
+    assert_eq!(unlocks[0].subject_name, "Chemistry");
+    assert_eq!(unlocks[0].code, "GY_02");
+    assert_eq!(unlocks[0].timetable_count, TimetableCount::Exact(1));
+  }
+}
@@ -1,19 +1,134 @@
-use std::fmt::{self, Display, Formatter};
+use std::{
+  fmt::{self, Display, Formatter},
+  hash::{DefaultHasher, Hash, Hasher},
+};
 
 use base64::{engine::general_purpose::URL_SAFE, Engine as _};
-use chrono::{NaiveTime, Weekday};
+use chrono::{Duration, NaiveTime, Weekday};
 use serde::{Deserialize, Serialize};
 use sha2::{
   digest::{generic_array::GenericArray, typenum::U32},
   Digest, Sha256,
 };
 
+use crate::text;
+
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Subject {
   pub name: String,
   pub courses: Vec<OneOfCourse>,
 }
 
+impl Subject {
+  /// Adds the course to the group of its course type, the same way the excel parser groups them.
+  pub fn add_course(&mut self, course: Course) {
+    let group = self.courses.iter_mut().find(|one_of_course| {
+      one_of_course
+        .first()
+        .is_some_and(|other| other.course_type == course.course_type)
+    });
+    match group {
+      Some(one_of_course) => one_of_course.push(course),
+      None => self.courses.push(vec![course]),
+    }
+  }
+
+  /// Replaces the course with the code, it's moved to another group if its course type changed.
+  pub fn replace_course(&mut self, code: &str, course: Course) {
+    let (group, index) = self
+      .courses
+      .iter()
+      .enumerate()
+      .find_map(|(group, one_of_course)| {
+        let index = one_of_course.iter().position(|other| other.code == code)?;
+        Some((group, index))
+      })
+      .unwrap();
+
+    if self.courses[group][index].course_type == course.course_type {
+      self.courses[group][index] = course;
+      return;
+    }
+    self.courses[group].remove(index);
+    if self.courses[group].is_empty() {
+      self.courses.remove(group);
+    }
+    self.add_course(course);
+  }
+}
+
+/// The course codes affected by merging a re-uploaded subject.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct MergeReport {
+  pub added: Vec<String>,
+  pub removed: Vec<String>,
+  pub changed: Vec<String>,
+  /// Courses added by the user, they are kept even though the uploaded subject doesn't have them.
+  pub kept: Vec<String>,
+}
+
+impl Subject {
+  /// Whether the subject has a course with any of the course codes of the other subject.
+  pub fn shares_courses_with(&self, other: &Subject) -> bool {
+    self.courses.iter().flatten().any(|course| {
+      other
+        .courses
+        .iter()
+        .flatten()
+        .any(|other| other.code == course.code)
+    })
+  }
+
+  /// Replaces the courses with the ones of the re-uploaded subject,
+  /// the flags set by the user are kept for the courses with the same code,
+  /// and so are the courses added by the user.
+  pub fn merge(&mut self, uploaded: Subject) -> MergeReport {
+    let mut report = MergeReport::default();
+    let mut courses = uploaded.courses;
+    for course in courses.iter_mut().flatten() {
+      course.subject_name = self.name.clone();
+      let previous = self
+        .courses
+        .iter()
+        .flatten()
+        .find(|previous| previous.code == course.code);
+      match previous {
+        Some(previous) => {
+          course.is_deleted = previous.is_deleted;
+          course.is_hidden_by_user = previous.is_hidden_by_user;
+          course.is_optional = previous.is_optional;
+          if !course.has_same_details(previous) {
+            report.changed.push(course.code.clone());
+          }
+        }
+        None => report.added.push(course.code.clone()),
+      }
+      course.update_hash();
+    }
+
+    let (kept, removed): (Vec<_>, Vec<_>) = self
+      .courses
+      .iter()
+      .flatten()
+      .filter(|previous| {
+        !courses
+          .iter()
+          .flatten()
+          .any(|course| course.code == previous.code)
+      })
+      .cloned()
+      .partition(|previous| previous.is_added_by_user);
+    report.removed = removed.into_iter().map(|previous| previous.code).collect();
+    self.courses = courses;
+    for course in kept {
+      report.kept.push(course.code.clone());
+      self.add_course(course);
+    }
+
+    report
+  }
+}
+
 pub trait UpdateSubjectsByCourseCode {
   fn update_subjects_by_course_code(&mut self, code: String, update_fn: Box<dyn Fn(&mut Course)>);
 }
@@ -36,13 +151,13 @@ pub type OneOfCourse = Vec<Course>;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct Timetable<'a> {
-  pub id: u32,
+  pub id: u64,
   pub courses: Vec<&'a Course>,
   hash: Option<String>,
 }
 
 impl<'a> Timetable<'a> {
-  pub fn new(id: u32, courses: Vec<&'a Course>) -> Timetable<'a> {
+  pub fn new(id: u64, courses: Vec<&'a Course>) -> Timetable<'a> {
     Timetable {
       id,
       courses,
@@ -85,8 +200,16 @@ pub struct Course {
   pub occurrence: Occurrence,
   #[serde(skip)]
   hash: GenericArray<u8, U32>,
+  #[serde(default)]
   pub is_deleted: bool,
+  #[serde(default)]
   pub is_hidden_by_user: bool,
+  /// Attended only if it fits, timetables are also generated without it.
+  #[serde(default)]
+  pub is_optional: bool,
+  /// Added by hand instead of an upload, a re-upload of the subject doesn't remove it.
+  #[serde(default)]
+  pub is_added_by_user: bool,
 }
 
 impl Course {
@@ -119,6 +242,8 @@ impl Course {
       hash: GenericArray::default(),
       is_deleted: false,
       is_hidden_by_user: false,
+      is_optional: false,
+      is_added_by_user: false,
     };
 
     course.update_hash();
@@ -131,14 +256,80 @@ impl Course {
     self.hash = Sha256::digest(serialized.as_bytes());
   }
 
+  /// A copy of the course made by the user, it needs a code of its own before it's added.
+  pub fn duplicate(&self) -> Course {
+    let mut course = self.clone();
+    course.code = String::new();
+    course.is_deleted = false;
+    course.is_hidden_by_user = false;
+    // it isn't in the uploaded file, so merging a re-upload would remove it otherwise
+    course.is_added_by_user = true;
+    course
+  }
+
+  /// Whether the courses only differ in the enrollment and the flags set by the user.
+  pub fn has_same_details(&self, other: &Course) -> bool {
+    self.course_type == other.course_type
+      && self.location == other.location
+      && self.teacher == other.teacher
+      && self.language == other.language
+      && self.site == other.site
+      && self.comment == other.comment
+      && self.description == other.description
+      && self.occurrence == other.occurrence
+  }
+
   pub fn is_ignored(&self) -> bool {
     self.is_deleted || self.is_hidden_by_user
   }
+
+  /// Neptun lists multiple teachers of a course in a single comma or semicolon separated cell.
+  pub fn teachers(&self) -> impl Iterator<Item = &str> {
+    self
+      .teacher
+      .split([',', ';'])
+      .map(str::trim)
+      .filter(|teacher| !teacher.is_empty())
+  }
+
+  pub fn has_teacher(&self, name: &str) -> bool {
+    self
+      .teachers()
+      .any(|teacher| text::fuzzy_contains(teacher, name))
+  }
+
+  /// A color derived from the course code, so a course has the same color in every timetable.
+  pub fn color(&self) -> [u8; 3] {
+    let mut hasher = DefaultHasher::new();
+    self.code.hash(&mut hasher);
+    let hash = hasher.finish();
+
+    let red = (hash & 0xFF) as u8;
+    let green = ((hash >> 8) & 0xFF) as u8;
+    let blue = ((hash >> 16) & 0xFF) as u8;
+    [red, green, blue]
+  }
+
+  /// The building part of the location, e.g. `BA` from `BA.F.05`.
+  pub fn building(&self) -> &str {
+    self.location.split('.').next().unwrap()
+  }
 }
 
 #[derive(
-  Debug, Clone, Serialize, Deserialize, Copy, PartialEq, Eq, PartialOrd, Ord, strum::Display,
+  Debug,
+  Clone,
+  Serialize,
+  Deserialize,
+  Copy,
+  PartialEq,
+  Eq,
+  PartialOrd,
+  Ord,
+  strum::Display,
+  strum::EnumString,
 )]
+#[strum(ascii_case_insensitive)]
 pub enum CourseType {
   Lecture,
   Laboratory,
@@ -161,6 +352,20 @@ pub struct Occurrence {
   pub end_time: NaiveTime,
 }
 
+impl Occurrence {
+  /// Whether the occurrence takes place in an odd or even week, unknown weeks are treated as every week.
+  pub fn occurs_in(&self, week: &Weeks) -> bool {
+    match &self.weeks {
+      None | Some(Weeks::Every) => true,
+      Some(weeks) => weeks == week,
+    }
+  }
+
+  pub fn duration(&self) -> Duration {
+    self.end_time - self.start_time
+  }
+}
+
 impl Display for Occurrence {
   fn fmt(&self, f: &mut Formatter) -> fmt::Result {
     write!(f, "{} {}-{}", self.weekday, self.start_time, self.end_time)
@@ -174,3 +379,102 @@ pub enum Weeks {
   Even,
   Odd,
 }
+
+#[cfg(test)]
+mod tests {
+  use chrono::{NaiveTime, Weekday};
+
+  use super::{Course, CourseType, Enrollment, Occurrence, Subject};
+
+  fn course(code: &str, teacher: &str) -> Course {
+    Course::new(
+      "Physics".to_string(),
+      code.to_string(),
+      CourseType::Practice,
+      Enrollment::default(),
+      "BA.F.05".to_string(),
+      teacher.to_string(),
+      String::new(),
+      String::new(),
+      String::new(),
+      String::new(),
+      Occurrence {
+        weeks: None,
+        weekday: Weekday::Mon,
+        start_time: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+        end_time: NaiveTime::from_hms_opt(9, 30, 0).unwrap(),
+      },
+    )
+  }
+
+  fn subject(courses: Vec<Course>) -> Subject {
+    Subject {
+      name: "Physics".to_string(),
+      courses: vec![courses],
+    }
+  }
+
+  fn codes(subject: &Subject) -> Vec<&str> {
+    let mut codes = subject
+      .courses
+      .iter()
+      .flatten()
+      .map(|course| course.code.as_str())
+      .collect::<Vec<_>>();
+    codes.sort();
+    codes
+  }
+
+  #[test]
+  fn merge_reports_added_removed_and_changed_courses() {
+    let mut hidden = course("GY_01", "John Doe");
+    hidden.is_hidden_by_user = true;
+    let mut existing = subject(vec![hidden, course("GY_02", "John Doe")]);
+    let uploaded = subject(vec![
+      course("GY_01", "Jane Doe"),
+      course("GY_03", "John Doe"),
+    ]);
+
+    let report = existing.merge(uploaded);
+
+    assert_eq!(report.added, ["GY_03"]);
+    assert_eq!(report.removed, ["GY_02"]);
+    assert_eq!(report.changed, ["GY_01"]);
+    assert!(report.kept.is_empty());
+    assert_eq!(codes(&existing), ["GY_01", "GY_03"]);
+    let merged = &existing.courses[0][0];
+    assert_eq!(merged.teacher, "Jane Doe");
+    assert!(merged.is_hidden_by_user);
+  }
+
+  #[test]
+  fn merge_keeps_courses_added_by_the_user() {
+    let mut added = course("MY_01", "John Doe");
+    added.is_added_by_user = true;
+    let mut existing = subject(vec![course("GY_01", "John Doe"), added]);
+    let uploaded = subject(vec![course("GY_01", "John Doe")]);
+
+    let report = existing.merge(uploaded);
+
+    assert!(report.added.is_empty());
+    assert!(report.removed.is_empty());
+    assert!(report.changed.is_empty());
+    assert_eq!(report.kept, ["MY_01"]);
+    assert_eq!(codes(&existing), ["GY_01", "MY_01"]);
+  }
+
+  #[test]
+  fn merge_keeps_duplicated_courses() {
+    let original = course("GY_01", "John Doe");
+    let mut duplicate = original.duplicate();
+    duplicate.code = "GY_01_COPY".to_string();
+    let mut existing = subject(vec![original, duplicate]);
+    let uploaded = subject(vec![course("GY_01", "John Doe")]);
+
+    let report = existing.merge(uploaded);
+
+    assert!(report.removed.is_empty());
+    assert_eq!(report.kept, ["GY_01_COPY"]);
+    assert_eq!(codes(&existing), ["GY_01", "GY_01_COPY"]);
+  }
+}
@@ -1,50 +1,158 @@
 use std::{
   io::{BufRead, Seek},
   slice::Iter,
+  sync::LazyLock,
 };
 
 use calamine::{Data, DataType as _, Reader, Xlsx};
 use chrono::{NaiveTime, Weekday};
 use itertools::Itertools;
+use regex::Regex;
 
 use crate::data::{Course, CourseType, Enrollment, Occurrence, Subject};
 
+/// Parses the courses of a subject from the excel file exported from Neptun,
+/// the error tells the first row that isn't a valid course.
 pub fn parse_subject<R: BufRead + Seek>(
   subject_name: String,
   excel: &mut Xlsx<R>,
-) -> Subject {
-  let sheet = &excel.worksheets()[0].1;
-  let courses = sheet
-    .rows()
-    .skip(1)
-    .map(|course| parse_course(subject_name.clone(), course))
+) -> Result<Subject, String> {
+  let worksheets = excel.worksheets();
+  let Some((_name, sheet)) = worksheets.first() else {
+    return Err("The file has no sheets".to_string());
+  };
+  parse_rows(subject_name, sheet.rows())
+}
+
+/// Parses the rows after the titles and the header, the columns of the courses come in a fixed order,
+/// apart from a column with the subject's name.
+fn parse_rows<'a>(
+  subject_name: String,
+  rows: impl Iterator<Item = &'a [Data]>,
+) -> Result<Subject, String> {
+  let rows = rows.collect_vec();
+  let header = header_row(&rows);
+  let subject_column = rows.get(header).and_then(|header| {
+    header
+      .iter()
+      .position(|cell| SUBJECT_COLUMN.is_match(&cell.to_string()))
+  });
+  let courses = rows
+    .iter()
+    .enumerate()
+    .skip(header + 1)
+    .map(|(i, row)| {
+      let row = row
+        .iter()
+        .enumerate()
+        .filter(|(column, _)| Some(*column) != subject_column)
+        .map(|(_, cell)| cell.clone())
+        .collect_vec();
+      parse_course(subject_name.clone(), &row).map_err(|e| format!("Row {}: {e}", i + 1))
+    })
+    .collect::<Result<Vec<_>, _>>()?
+    .into_iter()
     .sorted_by_key(|course| course.course_type)
     .chunk_by(|course| course.course_type)
     .into_iter()
     .map(|(_type, courses)| courses.collect_vec())
     .collect_vec();
-  Subject {
+  Ok(Subject {
     name: subject_name,
-    courses
+    courses,
+  })
+}
+
+/// A title with the subject's name after a label, e.g. `Tárgy: Analízis` above the courses.
+static SUBJECT_TITLE: LazyLock<Regex> = LazyLock::new(|| {
+  Regex::new(r"(?i)^\s*(?:(?:tan)?t[áa]rgy(?:\s*neve)?|subject(?:\s*name)?)\s*:\s*(.+?)\s*$")
+    .unwrap()
+});
+/// The header of a column with the subject's name in every row.
+static SUBJECT_COLUMN: LazyLock<Regex> = LazyLock::new(|| {
+  Regex::new(r"(?i)^\s*(?:(?:tan)?t[áa]rgy(?:\s*neve)?|subject(?:\s*name)?)\s*$").unwrap()
+});
+/// The ` (3)` suffix of repeated downloads.
+static DOWNLOAD_SUFFIX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s*\(\d+\)$").unwrap());
+/// The rows searched for the subject's name, the title and the header come before the courses.
+const HEADER_ROWS: usize = 3;
+
+/// The name of the subject, taken from the contents of the sheet when it has the name,
+/// otherwise from the file name without its extension and the ` (3)` suffix of repeated downloads.
+pub fn subject_name<R: BufRead + Seek>(file_name: &str, excel: &mut Xlsx<R>) -> String {
+  let worksheets = excel.worksheets();
+  let name_in_sheet = worksheets
+    .first()
+    .and_then(|(_name, sheet)| name_in_rows(sheet.rows()));
+  name_in_sheet.unwrap_or_else(|| name_from_file_name(file_name))
+}
+
+/// The name in a title of the first rows, or in the column with a subject header.
+fn name_in_rows<'a>(rows: impl Iterator<Item = &'a [Data]>) -> Option<String> {
+  let rows = rows.take(HEADER_ROWS + 1).collect_vec();
+  let title = rows
+    .iter()
+    .take(HEADER_ROWS)
+    .filter_map(|row| title(row))
+    .find_map(|title| Some(SUBJECT_TITLE.captures(&title)?[1].to_string()));
+  if title.is_some() {
+    return title;
   }
+
+  rows.iter().tuple_windows().find_map(|(header, row)| {
+    let column = header
+      .iter()
+      .position(|cell| SUBJECT_COLUMN.is_match(&cell.to_string()))?;
+    let name = row.get(column)?.to_string();
+    let name = name.trim();
+    (!name.is_empty()).then(|| name.to_string())
+  })
+}
+
+/// A title is the only cell of its row, so the comments of the courses aren't mistaken for it.
+fn title(row: &[Data]) -> Option<String> {
+  row
+    .iter()
+    .map(|cell| cell.to_string())
+    .filter(|cell| !cell.trim().is_empty())
+    .exactly_one()
+    .ok()
+}
+
+/// Index of the header row, after the titles and the empty rows above it.
+fn header_row(rows: &[&[Data]]) -> usize {
+  rows
+    .iter()
+    .take(HEADER_ROWS)
+    .take_while(|row| {
+      title(row).is_some() || row.iter().all(|cell| cell.to_string().trim().is_empty())
+    })
+    .count()
 }
 
-fn parse_course(subject_name: String, row: &[Data]) -> Course {
+fn name_from_file_name(file_name: &str) -> String {
+  let stem = file_name
+    .rsplit_once('.')
+    .map_or(file_name, |(stem, _extension)| stem);
+  DOWNLOAD_SUFFIX.replace(stem, "").trim().to_string()
+}
+
+fn parse_course(subject_name: String, row: &[Data]) -> Result<Course, String> {
   let mut r = row.iter();
 
-  let code = cell(&mut r);
-  let course_type = parse_course_type(&cell(&mut r));
-  let enrollment = parse_enrollment(cell(&mut r));
+  let code = cell(&mut r)?;
+  let course_type = parse_course_type(&cell(&mut r)?)?;
+  let enrollment = parse_enrollment(cell(&mut r)?)?;
   r.next();
   r.next();
-  let (occurrence, location) = parse_occurrence_and_location(cell(&mut r));
-  let teacher = cell(&mut r);
-  let language = cell(&mut r);
-  let site = cell(&mut r);
-  let comment = cell(&mut r);
-  let description = cell(&mut r);
-
-  Course::new(
+  let (occurrence, location) = parse_occurrence_and_location(cell(&mut r)?)?;
+  let teacher = cell(&mut r)?;
+  let language = cell(&mut r)?;
+  let site = cell(&mut r)?;
+  let comment = cell(&mut r)?;
+  let description = cell(&mut r)?;
+
+  Ok(Course::new(
     subject_name,
     code,
     course_type,
@@ -56,34 +164,40 @@ fn parse_course(subject_name: String, row: &[Data]) -> Course {
     comment,
     description,
     occurrence,
-  )
+  ))
 }
 
-fn parse_course_type(cell: &str) -> CourseType {
+pub(crate) fn parse_course_type(cell: &str) -> Result<CourseType, String> {
   match cell {
-    "Elmélet" => CourseType::Lecture,
-    "Labor" => CourseType::Laboratory,
-    "Gyakorlat" => CourseType::Practice,
-    "E-Learning" => CourseType::ELearning,
-    _ => panic!("Invalid course type: {cell}"),
+    "Elmélet" => Ok(CourseType::Lecture),
+    "Labor" => Ok(CourseType::Laboratory),
+    "Gyakorlat" => Ok(CourseType::Practice),
+    "E-Learning" => Ok(CourseType::ELearning),
+    _ => Err(format!("Invalid course type: {cell}")),
   }
 }
 
-fn parse_enrollment(cell: String) -> Enrollment {
+fn parse_enrollment(cell: String) -> Result<Enrollment, String> {
+  let invalid = || format!("Invalid enrollment: {cell}");
   let mut tokens = cell.split('/');
-  let mut parse_next = || tokens.next().unwrap().parse().unwrap();
-  Enrollment {
-    people_joined: parse_next(),
-    people_queue: parse_next(),
-    people_limit: parse_next(),
-  }
+  let mut parse_next = || -> Result<u32, String> {
+    let token = tokens.next().ok_or_else(invalid)?;
+    token.trim().parse().map_err(|_| invalid())
+  };
+  Ok(Enrollment {
+    people_joined: parse_next()?,
+    people_queue: parse_next()?,
+    people_limit: parse_next()?,
+  })
 }
 
-fn parse_occurrence_and_location(occ_and_loc: String) -> (Occurrence, String) {
+pub(crate) fn parse_occurrence_and_location(
+  occ_and_loc: String,
+) -> Result<(Occurrence, String), String> {
   // occurrence weeks are not stored in the excel file
   // occ_and_loc has even more types of format for PE courses which are not implemented
   if occ_and_loc.is_empty() {
-    return (
+    return Ok((
       Occurrence {
         weeks: None,
         weekday: Weekday::Mon,
@@ -91,20 +205,21 @@ fn parse_occurrence_and_location(occ_and_loc: String) -> (Occurrence, String) {
         end_time: NaiveTime::from_hms_opt(0, 0, 0).unwrap(),
       },
       String::new(),
-    );
+    ));
   }
+  let invalid = || format!("Invalid occurrence: {occ_and_loc}");
   let (occ_str, loc) = occ_and_loc.split_once("  ").unwrap_or(
     occ_and_loc
       .split_once(' ')
       .unwrap_or((occ_and_loc.as_str(), "N/A")),
   );
-  let (weekday_str, times_str) = occ_str.split_once(':').unwrap();
-  let weekday = parse_weekday(weekday_str);
-  let (start_str, end_str) = times_str.split_once('-').unwrap();
-  let parse_time = |str| NaiveTime::parse_from_str(str, "%H:%M").unwrap();
-  let start_time = parse_time(start_str);
-  let end_time = parse_time(end_str);
-  (
+  let (weekday_str, times_str) = occ_str.split_once(':').ok_or_else(invalid)?;
+  let weekday = parse_weekday(weekday_str)?;
+  let (start_str, end_str) = times_str.split_once('-').ok_or_else(invalid)?;
+  let parse_time = |str| NaiveTime::parse_from_str(str, "%H:%M").map_err(|_| invalid());
+  let start_time = parse_time(start_str)?;
+  let end_time = parse_time(end_str)?;
+  Ok((
     Occurrence {
       weeks: None,
       weekday,
@@ -112,22 +227,166 @@ fn parse_occurrence_and_location(occ_and_loc: String) -> (Occurrence, String) {
       end_time,
     },
     loc.to_string(),
-  )
+  ))
 }
 
-fn parse_weekday(weekday_str: &str) -> Weekday {
+fn parse_weekday(weekday_str: &str) -> Result<Weekday, String> {
   match weekday_str {
-    "H" => Weekday::Mon,
-    "K" => Weekday::Tue,
-    "SZE" => Weekday::Wed,
-    "CS" => Weekday::Thu,
-    "P" => Weekday::Fri,
-    "SZO" => Weekday::Sat,
-    "V" => Weekday::Sun,
-    _ => panic!("Invalid weekday: {weekday_str}"),
+    "H" => Ok(Weekday::Mon),
+    "K" => Ok(Weekday::Tue),
+    "SZE" => Ok(Weekday::Wed),
+    "CS" => Ok(Weekday::Thu),
+    "P" => Ok(Weekday::Fri),
+    "SZO" => Ok(Weekday::Sat),
+    "V" => Ok(Weekday::Sun),
+    _ => Err(format!("Invalid weekday: {weekday_str}")),
   }
 }
 
-fn cell(row: &mut Iter<'_, Data>) -> String {
-  row.next().unwrap().as_string().unwrap()
+fn cell(row: &mut Iter<'_, Data>) -> Result<String, String> {
+  match row.next() {
+    None => Err("Missing column".to_string()),
+    Some(Data::Empty) => Ok(String::new()),
+    Some(cell) => cell
+      .as_string()
+      .ok_or_else(|| format!("Invalid cell: {cell:?}")),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use calamine::Data;
+
+  use chrono::Weekday;
+
+  use super::{name_from_file_name, name_in_rows, parse_rows};
+  use crate::data::CourseType;
+
+  fn rows(rows: &[&[&str]]) -> Vec<Vec<Data>> {
+    rows
+      .iter()
+      .map(|row| {
+        row
+          .iter()
+          .map(|cell| match cell.is_empty() {
+            true => Data::Empty,
+            false => Data::String(cell.to_string()),
+          })
+          .collect()
+      })
+      .collect()
+  }
+
+  fn name(sheet: &[Vec<Data>]) -> Option<String> {
+    name_in_rows(sheet.iter().map(Vec::as_slice))
+  }
+
+  const HEADER: &[&str] = &[
+    "Kurzus kódja",
+    "Kurzus típusa",
+    "Létszám",
+    "Oktatási nyelv",
+    "Kurzus státusza",
+    "Órarend infó",
+    "Oktatók",
+    "Nyelv",
+    "Telephely",
+    "Megjegyzés",
+    "Leírás",
+  ];
+
+  fn course_row<'a>(code: &'a str, course_type: &'a str, time: &'a str) -> Vec<&'a str> {
+    vec![
+      code,
+      course_type,
+      "10/0/30",
+      "",
+      "",
+      time,
+      "John Doe",
+      "magyar",
+      "Budapest",
+      "",
+      "",
+    ]
+  }
+
+  #[test]
+  fn subject_name_is_read_from_the_title() {
+    let sheet = rows(&[
+      &["Tárgy: Analízis I.", ""],
+      &["Kurzus kódja", "Kurzus típusa"],
+      &["EA_01", "Elmélet"],
+    ]);
+    assert_eq!(name(&sheet).as_deref(), Some("Analízis I."));
+  }
+
+  #[test]
+  fn subject_name_is_read_from_the_subject_column() {
+    let sheet = rows(&[
+      &["Kurzus kódja", "Tárgy neve"],
+      &["EA_01", "Fizika"],
+      &["GY_01", "Fizika"],
+    ]);
+    assert_eq!(name(&sheet).as_deref(), Some("Fizika"));
+  }
+
+  #[test]
+  fn subject_name_falls_back_to_the_file_name() {
+    let sheet = rows(&[&["Kurzus kódja", "Kurzus típusa"], &["EA_01", "Elmélet"]]);
+    assert_eq!(name(&sheet), None);
+    assert_eq!(name_from_file_name("Fizika (3).xlsx"), "Fizika");
+  }
+
+  #[test]
+  fn sheet_with_a_title_is_parsed() {
+    let sheet = rows(&[
+      &["Tárgy: Analízis I."],
+      &[],
+      HEADER,
+      &course_row("EA_01", "Elmélet", "H:08:00-09:30  (BA.1.10)"),
+      &course_row("GY_01", "Gyakorlat", "K:10:00-11:30  (BA.2.05)"),
+      &course_row("GY_02", "Gyakorlat", "SZE:10:00-11:30  (BA.2.05)"),
+    ]);
+    let name = name(&sheet).unwrap();
+    let subject = parse_rows(name, sheet.iter().map(Vec::as_slice)).unwrap();
+
+    assert_eq!(subject.name, "Analízis I.");
+    assert_eq!(subject.courses.len(), 2);
+    let lecture = &subject.courses[0][0];
+    assert_eq!(lecture.code, "EA_01");
+    assert_eq!(lecture.course_type, CourseType::Lecture);
+    assert_eq!(lecture.subject_name, "Analízis I.");
+    let practices = subject.courses[1]
+      .iter()
+      .map(|c| &c.code)
+      .collect::<Vec<_>>();
+    assert_eq!(practices, ["GY_01", "GY_02"]);
+    assert_eq!(subject.courses[1][1].occurrence.weekday, Weekday::Wed);
+  }
+
+  #[test]
+  fn subject_column_is_left_out_of_the_courses() {
+    let mut header = HEADER.to_vec();
+    header.insert(1, "Tárgy neve");
+    let mut course = course_row("EA_01", "Elmélet", "H:08:00-09:30  (BA.1.10)");
+    course.insert(1, "Fizika");
+    let sheet = rows(&[&header, &course]);
+    let subject = parse_rows(name(&sheet).unwrap(), sheet.iter().map(Vec::as_slice)).unwrap();
+
+    assert_eq!(subject.name, "Fizika");
+    assert_eq!(subject.courses[0][0].code, "EA_01");
+    assert_eq!(subject.courses[0][0].teacher, "John Doe");
+  }
+
+  #[test]
+  fn invalid_course_reports_its_row_in_the_sheet() {
+    let sheet = rows(&[
+      &["Tárgy: Analízis I."],
+      HEADER,
+      &course_row("EA_01", "Szeminárium", "H:08:00-09:30  (BA.1.10)"),
+    ]);
+    let error = parse_rows(String::new(), sheet.iter().map(Vec::as_slice)).unwrap_err();
+    assert_eq!(error, "Row 3: Invalid course type: Szeminárium");
+  }
 }
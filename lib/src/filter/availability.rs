@@ -0,0 +1,54 @@
+use chrono::{NaiveTime, Weekday};
+
+use crate::{
+  data::Timetable,
+  filter::{self, Filter},
+};
+
+struct AvailabilityFilter(Vec<AvailableWindow>);
+
+struct AvailableWindow {
+  pub weekday: Weekday,
+  pub start: NaiveTime,
+  pub end: NaiveTime,
+}
+
+pub fn try_parse(spec: &str) -> Option<Result<Box<dyn Filter>, String>> {
+  filter::parse_with_key(spec, "availability", |value| {
+    let windows = value
+      .split(';')
+      .map(parse_window)
+      .collect::<Result<_, _>>()?;
+    Ok(AvailabilityFilter(windows))
+  })
+}
+
+fn parse_window(value: &str) -> Result<AvailableWindow, String> {
+  let (weekday, times) = value
+    .split_once(',')
+    .ok_or(format!("Not enough comma seperated arguments: {value}"))?;
+  let (start, end) = times
+    .split_once('-')
+    .ok_or(format!("Invalid time range: {times}"))?;
+  Ok(AvailableWindow {
+    weekday: filter::parse_weekday(weekday)?,
+    start: filter::parse_time(start)?,
+    end: filter::parse_time(end)?,
+  })
+}
+
+impl Filter for AvailabilityFilter {
+  fn filter(&self, timetable: &Timetable) -> bool {
+    timetable
+      .courses
+      .iter()
+      .map(|course| &course.occurrence)
+      .all(|occ| {
+        self.0.iter().any(|window| {
+          window.weekday == occ.weekday
+            && window.start <= occ.start_time
+            && occ.end_time <= window.end
+        })
+      })
+  }
+}
@@ -0,0 +1,34 @@
+use chrono::{NaiveTime, Weekday};
+
+use crate::{
+  data::Timetable,
+  filter::{self, Filter},
+};
+
+struct EndBeforeFilter {
+  pub weekday: Weekday,
+  pub end: NaiveTime,
+}
+
+pub fn try_parse(spec: &str) -> Option<Result<Box<dyn Filter>, String>> {
+  filter::parse_with_key(spec, "end_before", |value| {
+    let (weekday, end) = value
+      .split_once(',')
+      .ok_or(format!("Not enough comma seperated arguments: {value}"))?;
+    Ok(EndBeforeFilter {
+      weekday: filter::parse_weekday(weekday)?,
+      end: filter::parse_time(end)?,
+    })
+  })
+}
+
+impl Filter for EndBeforeFilter {
+  fn filter(&self, timetable: &Timetable) -> bool {
+    timetable
+      .courses
+      .iter()
+      .map(|course| &course.occurrence)
+      .filter(|occ| occ.weekday == self.weekday)
+      .all(|occ| occ.end_time <= self.end)
+  }
+}
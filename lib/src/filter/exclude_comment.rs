@@ -0,0 +1,31 @@
+use regex::Regex;
+
+use crate::{
+  data::Timetable,
+  filter::{self, Filter, SubjectConstraint},
+};
+
+struct ExcludeCommentFilter {
+  pub scope: SubjectConstraint,
+  pub regex: Regex,
+}
+
+pub fn try_parse(spec: &str) -> Option<Result<Box<dyn Filter>, String>> {
+  filter::parse_with_key(spec, "exclude_comment", |value| {
+    let scope = SubjectConstraint::parse_regex(value)?;
+    let regex = filter::parse_regex(&scope.value)?;
+    Ok(ExcludeCommentFilter { scope, regex })
+  })
+}
+
+impl Filter for ExcludeCommentFilter {
+  fn filter(&self, timetable: &Timetable) -> bool {
+    timetable
+      .courses
+      .iter()
+      .filter(|course| self.scope.applies_to(course))
+      .all(|course| {
+        !self.regex.is_match(&course.comment) && !self.regex.is_match(&course.description)
+      })
+  }
+}
@@ -0,0 +1,36 @@
+use wildmatch::WildMatch;
+
+use crate::{
+  data::{Subject, Timetable},
+  filter::{self, Filter},
+};
+
+struct ExcludeCourseFilter(WildMatch);
+
+pub fn try_parse(spec: &str) -> Option<Result<Box<dyn Filter>, String>> {
+  filter::parse_with_key(spec, "exclude_course", |value| {
+    Ok(ExcludeCourseFilter(WildMatch::new(value)))
+  })
+}
+
+impl Filter for ExcludeCourseFilter {
+  fn filter(&self, timetable: &Timetable) -> bool {
+    timetable
+      .courses
+      .iter()
+      .all(|course| !self.0.matches(&course.code))
+  }
+
+  fn updates_courses(&self) -> bool {
+    true
+  }
+
+  fn update_courses(&self, subjects: &mut [Subject]) {
+    subjects
+      .iter_mut()
+      .flat_map(|subject| &mut subject.courses)
+      .flatten()
+      .filter(|course| self.0.matches(&course.code))
+      .for_each(|course| course.is_hidden_by_user = true);
+  }
+}
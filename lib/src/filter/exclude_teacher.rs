@@ -0,0 +1,22 @@
+use crate::{
+  data::Timetable,
+  filter::{self, Filter, SubjectConstraint},
+};
+
+struct ExcludeTeacherFilter(SubjectConstraint);
+
+pub fn try_parse(spec: &str) -> Option<Result<Box<dyn Filter>, String>> {
+  filter::parse_with_key(spec, "exclude_teacher", |value| {
+    Ok(ExcludeTeacherFilter(SubjectConstraint::parse(value)?))
+  })
+}
+
+impl Filter for ExcludeTeacherFilter {
+  fn filter(&self, timetable: &Timetable) -> bool {
+    timetable
+      .courses
+      .iter()
+      .filter(|course| self.0.applies_to(course))
+      .all(|course| !course.has_teacher(&self.0.value))
+  }
+}
@@ -0,0 +1,37 @@
+use chrono::Weekday;
+
+use crate::{
+  data::Timetable,
+  filter::{self, Filter},
+};
+
+struct FreeWeekdaysFilter {
+  /// Any of the inner sets of weekdays has to be completely free.
+  pub alternatives: Vec<Vec<Weekday>>,
+  pub ignore_optional: bool,
+}
+
+pub fn try_parse(spec: &str) -> Option<Result<Box<dyn Filter>, String>> {
+  filter::parse_with_key(spec, "free_weekdays", |value| {
+    let (value, ignore_optional) = filter::parse_ignore_optional(value);
+    let alternatives = value
+      .split('|')
+      .map(|weekdays| weekdays.split('+').map(filter::parse_weekday).collect())
+      .collect::<Result<_, _>>()?;
+    Ok(FreeWeekdaysFilter {
+      alternatives,
+      ignore_optional,
+    })
+  })
+}
+
+impl Filter for FreeWeekdaysFilter {
+  fn filter(&self, timetable: &Timetable) -> bool {
+    let busy_weekdays = filter::busy_weekdays(timetable, self.ignore_optional);
+    self.alternatives.iter().any(|weekdays| {
+      weekdays
+        .iter()
+        .all(|weekday| !busy_weekdays.contains(weekday))
+    })
+  }
+}
@@ -0,0 +1,48 @@
+use chrono::Weekday;
+
+use crate::{
+  data::Timetable,
+  filter::{self, Filter},
+};
+
+const WORKDAYS: &[Weekday] = &[
+  Weekday::Mon,
+  Weekday::Tue,
+  Weekday::Wed,
+  Weekday::Thu,
+  Weekday::Fri,
+];
+
+struct FreeWorkdays {
+  pub days: usize,
+  pub ignore_optional: bool,
+}
+
+pub fn try_parse(spec: &str) -> Option<Result<Box<dyn Filter>, String>> {
+  filter::parse_with_key(spec, "free_workdays", |value| {
+    let (days, ignore_optional) = filter::parse_ignore_optional(value);
+    let days = days
+      .parse::<usize>()
+      .map_err(|_| format!("Invalid positive number: {days}"))?;
+    if days > WORKDAYS.len() {
+      return Err(format!(
+        "There are only {} workdays: {days}",
+        WORKDAYS.len()
+      ));
+    }
+    Ok(FreeWorkdays {
+      days,
+      ignore_optional,
+    })
+  })
+}
+
+impl Filter for FreeWorkdays {
+  fn filter(&self, timetable: &Timetable) -> bool {
+    filter::busy_weekdays(timetable, self.ignore_optional)
+      .iter()
+      .filter(|weekday| WORKDAYS.contains(weekday))
+      .count()
+      <= WORKDAYS.len() - self.days
+  }
+}
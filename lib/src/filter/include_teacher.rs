@@ -0,0 +1,22 @@
+use crate::{
+  data::Timetable,
+  filter::{self, Filter, SubjectConstraint},
+};
+
+struct IncludeTeacherFilter(SubjectConstraint);
+
+pub fn try_parse(spec: &str) -> Option<Result<Box<dyn Filter>, String>> {
+  filter::parse_with_key(spec, "include_teacher", |value| {
+    Ok(IncludeTeacherFilter(SubjectConstraint::parse(value)?))
+  })
+}
+
+impl Filter for IncludeTeacherFilter {
+  fn filter(&self, timetable: &Timetable) -> bool {
+    timetable
+      .courses
+      .iter()
+      .filter(|course| self.0.applies_to(course))
+      .any(|course| course.has_teacher(&self.0.value))
+  }
+}
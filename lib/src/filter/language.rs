@@ -0,0 +1,23 @@
+use crate::{
+  data::Timetable,
+  filter::{self, Filter, SubjectConstraint},
+  text,
+};
+
+struct LanguageFilter(SubjectConstraint);
+
+pub fn try_parse(spec: &str) -> Option<Result<Box<dyn Filter>, String>> {
+  filter::parse_with_key(spec, "language", |value| {
+    Ok(LanguageFilter(SubjectConstraint::parse(value)?))
+  })
+}
+
+impl Filter for LanguageFilter {
+  fn filter(&self, timetable: &Timetable) -> bool {
+    timetable
+      .courses
+      .iter()
+      .filter(|course| self.0.applies_to(course))
+      .all(|course| text::fuzzy_contains(&course.language, &self.0.value))
+  }
+}
@@ -0,0 +1,56 @@
+use chrono::{Duration, NaiveTime};
+
+use crate::{
+  data::Timetable,
+  filter::{self, Filter},
+};
+
+struct LunchBreakFilter {
+  pub start: NaiveTime,
+  pub end: NaiveTime,
+  pub length: Duration,
+}
+
+pub fn try_parse(spec: &str) -> Option<Result<Box<dyn Filter>, String>> {
+  filter::parse_with_key(spec, "lunch_break", |value| {
+    let (window, length) = value
+      .split_once(',')
+      .ok_or(format!("Not enough comma seperated arguments: {value}"))?;
+    let (start, end) = window
+      .split_once('-')
+      .ok_or(format!("Invalid time range: {window}"))?;
+    let (start, end) = (filter::parse_time(start)?, filter::parse_time(end)?);
+    if start >= end {
+      return Err(format!("The time range ends before it starts: {window}"));
+    }
+    let length = length
+      .parse::<u32>()
+      .ok()
+      .and_then(|length| Duration::try_minutes(length.into()))
+      .ok_or(format!("Invalid positive number: {length}"))?;
+    Ok(LunchBreakFilter { start, end, length })
+  })
+}
+
+impl Filter for LunchBreakFilter {
+  fn filter(&self, timetable: &Timetable) -> bool {
+    filter::courses_per_day(timetable).iter().all(|courses| {
+      // the free time starts at the window's start or after a course ending inside the window
+      let mut free_from = self.start;
+      for occ in courses.iter().map(|course| &course.occurrence) {
+        if occ.end_time <= self.start {
+          continue;
+        }
+        if occ.start_time >= self.end {
+          break;
+        }
+        if occ.start_time.max(self.start) - free_from >= self.length {
+          return true;
+        }
+        free_from = free_from.max(occ.end_time);
+      }
+
+      self.end - free_from >= self.length
+    })
+  }
+}
@@ -0,0 +1,58 @@
+use chrono::Duration;
+use itertools::Itertools;
+
+use crate::{
+  data::Timetable,
+  filter::{self, Filter},
+};
+
+/// Gaps shorter than this are only for changing rooms, not a real break.
+const DEFAULT_MIN_BREAK_MINUTES: i64 = 15;
+
+struct MaxConsecutiveMinutesFilter {
+  pub max_consecutive: Duration,
+  pub min_break: Duration,
+}
+
+pub fn try_parse(spec: &str) -> Option<Result<Box<dyn Filter>, String>> {
+  filter::parse_with_key(spec, "max_consecutive_minutes", |value| {
+    let (max_consecutive, min_break) = match value.split_once(',') {
+      Some((max_consecutive, min_break)) => (max_consecutive, min_break),
+      None => (value, ""),
+    };
+    let parse_minutes = |minutes: &str| {
+      minutes
+        .parse::<u32>()
+        .ok()
+        .and_then(|minutes| Duration::try_minutes(minutes.into()))
+        .ok_or(format!("Invalid positive number: {minutes}"))
+    };
+    Ok(MaxConsecutiveMinutesFilter {
+      max_consecutive: parse_minutes(max_consecutive)?,
+      min_break: if min_break.is_empty() {
+        Duration::minutes(DEFAULT_MIN_BREAK_MINUTES)
+      } else {
+        parse_minutes(min_break)?
+      },
+    })
+  })
+}
+
+impl Filter for MaxConsecutiveMinutesFilter {
+  fn filter(&self, timetable: &Timetable) -> bool {
+    filter::courses_per_day(timetable).iter().all(|courses| {
+      courses
+        .iter()
+        .map(|course| (course.occurrence.start_time, course.occurrence.end_time))
+        // merge courses without a real break between them into a single block
+        .coalesce(|(start, end), (next_start, next_end)| {
+          if next_start - end < self.min_break {
+            Ok((start, next_end))
+          } else {
+            Err(((start, end), (next_start, next_end)))
+          }
+        })
+        .all(|(start, end)| end - start <= self.max_consecutive)
+    })
+  }
+}
@@ -0,0 +1,23 @@
+use crate::{
+  data::Timetable,
+  filter::{self, Filter},
+};
+
+struct MaxCoursesPerDayFilter(usize);
+
+pub fn try_parse(spec: &str) -> Option<Result<Box<dyn Filter>, String>> {
+  filter::parse_with_key(spec, "max_courses_per_day", |value| {
+    let courses = value
+      .parse::<usize>()
+      .map_err(|_| format!("Invalid positive number: {value}"))?;
+    Ok(MaxCoursesPerDayFilter(courses))
+  })
+}
+
+impl Filter for MaxCoursesPerDayFilter {
+  fn filter(&self, timetable: &Timetable) -> bool {
+    filter::courses_per_day(timetable)
+      .iter()
+      .all(|courses| courses.len() <= self.0)
+  }
+}
@@ -1,7 +1,9 @@
 use chrono::NaiveTime;
 
-use crate::filter::{self, Filter};
-use timetable_optimizer_lib::data::Timetable;
+use crate::{
+  data::Timetable,
+  filter::{self, Filter},
+};
 
 struct MaxEndTimeFilter(NaiveTime);
 
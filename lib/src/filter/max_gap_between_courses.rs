@@ -1,8 +1,10 @@
 use chrono::Duration;
 use itertools::Itertools;
 
-use crate::filter::{self, Filter};
-use timetable_optimizer_lib::data::Timetable;
+use crate::{
+  data::Timetable,
+  filter::{self, Filter},
+};
 
 struct MaxGapBetweenCoursesFilter(Duration);
 
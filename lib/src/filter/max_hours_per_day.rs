@@ -0,0 +1,33 @@
+use chrono::Duration;
+
+use crate::{
+  data::Timetable,
+  filter::{self, Filter},
+};
+
+struct MaxHoursPerDayFilter(Duration);
+
+pub fn try_parse(spec: &str) -> Option<Result<Box<dyn Filter>, String>> {
+  filter::parse_with_key(spec, "max_hours_per_day", |value| {
+    // the cast saturates, so the minutes of too many hours are out of range
+    let max_hours = value
+      .parse::<f64>()
+      .ok()
+      .filter(|hours| hours.is_finite() && *hours >= 0.0)
+      .and_then(|hours| Duration::try_minutes((hours * 60.0).round() as i64))
+      .ok_or(format!("Invalid positive number: {value}"))?;
+    Ok(MaxHoursPerDayFilter(max_hours))
+  })
+}
+
+impl Filter for MaxHoursPerDayFilter {
+  fn filter(&self, timetable: &Timetable) -> bool {
+    filter::courses_per_day(timetable).iter().all(|courses| {
+      courses
+        .iter()
+        .map(|course| course.occurrence.duration())
+        .sum::<Duration>()
+        <= self.0
+    })
+  }
+}
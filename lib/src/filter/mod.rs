@@ -0,0 +1,411 @@
+use std::ops::Range;
+
+use chrono::{NaiveTime, Weekday};
+use itertools::Itertools;
+use regex::Regex;
+
+use crate::{
+  data::{Course, Subject, Timetable, Weeks},
+  text,
+};
+
+pub mod availability;
+pub mod end_before;
+pub mod exclude_comment;
+pub mod exclude_course;
+pub mod exclude_site;
+pub mod exclude_teacher;
+pub mod free_weekdays;
+pub mod free_workdays;
+pub mod include_comment;
+pub mod include_teacher;
+pub mod language;
+pub mod lunch_break;
+pub mod max_consecutive_minutes;
+pub mod max_courses_per_day;
+pub mod max_end_time;
+pub mod max_gap_between_courses;
+pub mod max_hours_per_day;
+pub mod min_start_time;
+pub mod no_course_between;
+pub mod optional_course_type;
+pub mod require_course;
+pub mod site;
+pub mod start_after;
+pub mod travel_times;
+
+pub trait Filter {
+  fn filter(&self, timetable: &Timetable) -> bool;
+
+  /// Updates the courses before the timetables are generated,
+  /// e.g. hides the ones that can't be in any passing timetable.
+  fn update_courses(&self, _subjects: &mut [Subject]) {}
+
+  /// Whether the filter takes effect through `update_courses`, so the generated timetables all pass it.
+  fn updates_courses(&self) -> bool {
+    false
+  }
+}
+
+fn parse_with_key<F, T>(
+  spec: &str,
+  key: &str,
+  parse_fn: F,
+) -> Option<Result<Box<dyn Filter>, String>>
+where
+  F: FnOnce(&str) -> Result<T, String>,
+  T: Filter + 'static,
+{
+  // TODO: remove when fixed: https://github.com/rust-lang/rust-clippy/issues/12659
+  #[allow(clippy::manual_map)]
+  match spec.strip_prefix(&(key.to_string() + "=")) {
+    Some(value) => Some(match parse_fn(value) {
+      Ok(filter) => Ok(Box::new(filter)),
+      Err(e) => Err(e),
+    }),
+    None => None,
+  }
+}
+
+fn parse_weekday(value: &str) -> Result<Weekday, String> {
+  value
+    .parse::<Weekday>()
+    .map_err(|_| format!("Invalid weekday: {value}"))
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+  NaiveTime::parse_from_str(value, "%H:%M").map_err(|_| format!("Invalid time: {value}"))
+}
+
+/// Splits the `ignore_optional` flag from the end of the value: `1,ignore_optional`
+fn parse_ignore_optional(value: &str) -> (&str, bool) {
+  match value.strip_suffix(",ignore_optional") {
+    Some(value) => (value, true),
+    None => (value, false),
+  }
+}
+
+fn parse_regex(value: &str) -> Result<Regex, String> {
+  Regex::new(value).map_err(|e| format!("Invalid regex {value}: {e}"))
+}
+
+/// Weekdays that have at least one course, courses are expected to be sorted by weekday.
+pub(crate) fn busy_weekdays(timetable: &Timetable, ignore_optional: bool) -> Vec<Weekday> {
+  timetable
+    .courses
+    .iter()
+    .filter(|course| !(ignore_optional && course.is_optional))
+    .chunk_by(|course| course.occurrence.weekday)
+    .into_iter()
+    .map(|(weekday, _courses)| weekday)
+    .collect()
+}
+
+/// Courses of each weekday, split into odd and even weeks when a course doesn't occur every week.
+/// Courses are expected to be sorted by weekday.
+pub(crate) fn courses_per_day<'a>(timetable: &Timetable<'a>) -> Vec<Vec<&'a Course>> {
+  courses_per_weekday(timetable)
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// The courses of each weekday, as a single day or as an odd and an even week's day.
+pub(crate) fn courses_per_weekday<'a>(timetable: &Timetable<'a>) -> Vec<Vec<Vec<&'a Course>>> {
+  timetable
+    .courses
+    .iter()
+    .chunk_by(|course| course.occurrence.weekday)
+    .into_iter()
+    .map(|(_weekday, courses)| {
+      let courses = courses.copied().collect_vec();
+      let has_alternating_weeks = courses
+        .iter()
+        .any(|course| matches!(course.occurrence.weeks, Some(Weeks::Odd | Weeks::Even)));
+      if has_alternating_weeks {
+        [Weeks::Odd, Weeks::Even]
+          .iter()
+          .map(|week| {
+            courses
+              .iter()
+              .copied()
+              .filter(|course| course.occurrence.occurs_in(week))
+              .collect_vec()
+          })
+          .collect_vec()
+      } else {
+        vec![courses]
+      }
+    })
+    .collect()
+}
+
+/// A value, optionally limited to the courses of a single subject: `subject:value`.
+struct SubjectConstraint {
+  pub subject: Option<String>,
+  pub value: String,
+}
+
+impl SubjectConstraint {
+  fn parse(value: &str) -> Result<SubjectConstraint, String> {
+    // subject names can contain `:`, the values of these filters can't
+    match value.rsplit_once(':') {
+      Some((subject, constraint)) => SubjectConstraint::new(Some(subject), constraint, value),
+      None => SubjectConstraint::new(None, value, value),
+    }
+  }
+
+  /// Regexes can contain `:`, so their subject is given with an explicit prefix: `subject=name;regex`.
+  fn parse_regex(value: &str) -> Result<SubjectConstraint, String> {
+    match value.strip_prefix("subject=") {
+      Some(scoped) => {
+        let Some((subject, regex)) = scoped.split_once(';') else {
+          return Err(format!("Expected subject=name;regex: {value}"));
+        };
+        SubjectConstraint::new(Some(subject), regex, value)
+      }
+      None => SubjectConstraint::new(None, value, value),
+    }
+  }
+
+  fn new(
+    subject: Option<&str>,
+    constraint: &str,
+    value: &str,
+  ) -> Result<SubjectConstraint, String> {
+    if constraint.is_empty() {
+      return Err(format!("Missing value: {value}"));
+    }
+
+    Ok(SubjectConstraint {
+      subject: subject.map(str::to_string),
+      value: constraint.to_string(),
+    })
+  }
+
+  fn applies_to(&self, course: &Course) -> bool {
+    match &self.subject {
+      Some(subject) => text::normalize(&course.subject_name) == text::normalize(subject),
+      None => true,
+    }
+  }
+}
+
+/// Checks the specification without reading the files it refers to,
+/// so it's cheap enough to run on every key press.
+pub fn check_syntax(spec: &str) -> Result<(), String> {
+  match travel_times::check_syntax(spec) {
+    Some(result) => result,
+    None => parse_filter(spec).map(|_| ()),
+  }
+}
+
+pub fn parse_filter(spec: &str) -> Result<Box<dyn Filter>, String> {
+  let parsers = &[
+    min_start_time::try_parse,
+    max_end_time::try_parse,
+    free_workdays::try_parse,
+    max_gap_between_courses::try_parse,
+    exclude_teacher::try_parse,
+    no_course_between::try_parse,
+    travel_times::try_parse,
+    start_after::try_parse,
+    end_before::try_parse,
+    availability::try_parse,
+    free_weekdays::try_parse,
+    include_teacher::try_parse,
+    require_course::try_parse,
+    exclude_course::try_parse,
+    max_hours_per_day::try_parse,
+    max_courses_per_day::try_parse,
+    max_consecutive_minutes::try_parse,
+    lunch_break::try_parse,
+    language::try_parse,
+    site::try_parse,
+    exclude_site::try_parse,
+    include_comment::try_parse,
+    exclude_comment::try_parse,
+    optional_course_type::try_parse,
+  ];
+
+  parsers
+    .iter()
+    .find_map(|parser| parser(spec))
+    .ok_or_else(|| format!("Invalid filter specification: {spec}"))?
+}
+
+/// Splits the input at spaces, except inside double quotes, so values can contain spaces:
+/// `exclude_teacher="John Doe"`
+pub fn split_specs(specs: &str) -> Vec<String> {
+  spec_ranges(specs)
+    .into_iter()
+    .map(|range| specs[range].replace('"', ""))
+    .collect()
+}
+
+/// Byte ranges of the space separated specs, quotes included.
+pub fn spec_ranges(specs: &str) -> Vec<Range<usize>> {
+  let mut ranges = vec![];
+  let mut start = None;
+  let mut in_quotes = false;
+  for (i, char) in specs.char_indices() {
+    match char {
+      ' ' if !in_quotes => {
+        if let Some(start) = start.take() {
+          ranges.push(start..i);
+        }
+      }
+      _ => {
+        if char == '"' {
+          in_quotes = !in_quotes;
+        }
+        start.get_or_insert(i);
+      }
+    }
+  }
+  if let Some(start) = start {
+    ranges.push(start..specs.len());
+  }
+
+  ranges
+}
+
+pub fn update_courses(subjects: &[Subject], filters: &[&dyn Filter]) -> Vec<Subject> {
+  let mut subjects = subjects.to_vec();
+  for filter in filters {
+    filter.update_courses(&mut subjects);
+  }
+
+  subjects
+}
+
+pub fn filter_timetables<'a>(
+  timetables: Vec<Timetable<'a>>,
+  filters: &[&dyn Filter],
+) -> Vec<Timetable<'a>> {
+  timetables
+    .into_iter()
+    .filter(|timetable| filters.iter().all(|filter| filter.filter(timetable)))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use chrono::{NaiveTime, Weekday};
+
+  use super::SubjectConstraint;
+  use crate::data::{Course, CourseType, Enrollment, Occurrence};
+
+  fn course(subject_name: &str) -> Course {
+    let time = NaiveTime::from_hms_opt(8, 0, 0).unwrap();
+    Course::new(
+      subject_name.to_string(),
+      "CODE".to_string(),
+      CourseType::Lecture,
+      Enrollment::default(),
+      String::new(),
+      String::new(),
+      String::new(),
+      String::new(),
+      String::new(),
+      String::new(),
+      Occurrence {
+        weeks: None,
+        weekday: Weekday::Mon,
+        start_time: time,
+        end_time: time,
+      },
+    )
+  }
+
+  #[test]
+  fn subject_is_matched_exactly() {
+    let constraint = SubjectConstraint::parse("Mathematics:John").unwrap();
+    assert!(constraint.applies_to(&course("Mathematics")));
+    assert!(constraint.applies_to(&course("mathematics")));
+    assert!(!constraint.applies_to(&course("Basic Mathematics")));
+
+    let constraint = SubjectConstraint::parse("Physics: Mechanics:John").unwrap();
+    assert_eq!(constraint.subject.as_deref(), Some("Physics: Mechanics"));
+    assert_eq!(constraint.value, "John");
+  }
+
+  #[test]
+  fn regex_with_colons_is_not_split() {
+    for regex in [r"\d{2}:\d{2}", "(?:online|remote)", "https://example.edu"] {
+      let constraint = SubjectConstraint::parse_regex(regex).unwrap();
+      assert_eq!(constraint.subject, None);
+      assert_eq!(constraint.value, regex);
+    }
+  }
+
+  #[test]
+  fn regex_subject_is_given_with_a_prefix() {
+    let constraint = SubjectConstraint::parse_regex(r"subject=Physics;\d{2}:\d{2}").unwrap();
+    assert_eq!(constraint.subject.as_deref(), Some("Physics"));
+    assert_eq!(constraint.value, r"\d{2}:\d{2}");
+
+    assert!(SubjectConstraint::parse_regex("subject=Physics").is_err());
+    assert!(SubjectConstraint::parse_regex("subject=Physics;").is_err());
+  }
+
+  #[test]
+  fn inline_travel_times_need_a_prefix() {
+    assert_eq!(
+      super::check_syntax("travel_times=csv:BA,BC,10;BC,TG,15"),
+      Ok(())
+    );
+    assert!(super::check_syntax("travel_times=csv:BA,BC,ten").is_err());
+    // a comma in a file name doesn't make it inline data
+    assert_eq!(super::check_syntax("travel_times=data/a,b.csv"), Ok(()));
+    assert!(super::parse_filter("travel_times=data/missing,file.csv")
+      .err()
+      .unwrap()
+      .starts_with("Failed to read file data/missing,file.csv"));
+  }
+
+  #[test]
+  fn workload_limits_out_of_range_are_rejected() {
+    for spec in [
+      "max_hours_per_day=inf",
+      "max_hours_per_day=NaN",
+      "max_hours_per_day=1e300",
+      "max_hours_per_day=-1",
+      "max_consecutive_minutes=9223372036854775807",
+      "max_consecutive_minutes=-90",
+      "max_consecutive_minutes=90,9223372036854775807",
+    ] {
+      assert!(super::check_syntax(spec).is_err(), "{spec}");
+    }
+    assert_eq!(super::check_syntax("max_hours_per_day=4.5"), Ok(()));
+    assert_eq!(
+      super::check_syntax("max_consecutive_minutes=180,10"),
+      Ok(())
+    );
+  }
+
+  #[test]
+  fn invalid_lunch_breaks_are_rejected() {
+    for (spec, error) in [
+      (
+        "lunch_break=11:00-14:00,-30",
+        "Invalid positive number: -30",
+      ),
+      (
+        "lunch_break=11:00-14:00,9223372036854775807",
+        "Invalid positive number: 9223372036854775807",
+      ),
+      (
+        "lunch_break=14:00-11:00,30",
+        "The time range ends before it starts: 14:00-11:00",
+      ),
+      (
+        "lunch_break=12:00-12:00,30",
+        "The time range ends before it starts: 12:00-12:00",
+      ),
+    ] {
+      assert_eq!(super::check_syntax(spec), Err(error.to_string()));
+    }
+    assert_eq!(super::check_syntax("lunch_break=11:30-14:00,45"), Ok(()));
+  }
+}
@@ -1,7 +1,9 @@
 use chrono::{NaiveTime, Weekday};
 
-use crate::filter::{self, Filter};
-use timetable_optimizer_lib::data::Timetable;
+use crate::{
+  data::Timetable,
+  filter::{self, Filter},
+};
 
 struct NoCourseBetweenFilter {
   pub weekday: Weekday,
@@ -17,15 +19,10 @@ pub fn try_parse(spec: &str) -> Option<Result<Box<dyn Filter>, String>> {
         .next()
         .ok_or(format!("Not enough comma seperated arguments: {value}"))
     };
-    let weekday = token()?;
     Ok(NoCourseBetweenFilter {
-      weekday: weekday
-        .parse::<Weekday>()
-        .map_err(|_| format!("Invalid weekday: {weekday}"))?,
-      start: NaiveTime::parse_from_str(token()?, "%H:%M")
-        .map_err(|_| format!("Invalid time: {value}"))?,
-      end: NaiveTime::parse_from_str(token()?, "%H:%M")
-        .map_err(|_| format!("Invalid time: {value}"))?,
+      weekday: filter::parse_weekday(token()?)?,
+      start: filter::parse_time(token()?)?,
+      end: filter::parse_time(token()?)?,
     })
   })
 }
@@ -0,0 +1,39 @@
+use crate::{
+  data::{CourseType, Subject, Timetable},
+  filter::{self, Filter, SubjectConstraint},
+};
+
+struct OptionalCourseTypeFilter {
+  pub scope: SubjectConstraint,
+  pub course_type: CourseType,
+}
+
+pub fn try_parse(spec: &str) -> Option<Result<Box<dyn Filter>, String>> {
+  filter::parse_with_key(spec, "optional_course_type", |value| {
+    let scope = SubjectConstraint::parse(value)?;
+    let course_type = scope
+      .value
+      .parse::<CourseType>()
+      .map_err(|_| format!("Invalid course type: {}", scope.value))?;
+    Ok(OptionalCourseTypeFilter { scope, course_type })
+  })
+}
+
+impl Filter for OptionalCourseTypeFilter {
+  fn filter(&self, _timetable: &Timetable) -> bool {
+    true
+  }
+
+  fn updates_courses(&self) -> bool {
+    true
+  }
+
+  fn update_courses(&self, subjects: &mut [Subject]) {
+    subjects
+      .iter_mut()
+      .flat_map(|subject| &mut subject.courses)
+      .flatten()
+      .filter(|course| course.course_type == self.course_type && self.scope.applies_to(course))
+      .for_each(|course| course.is_optional = true);
+  }
+}
@@ -0,0 +1,41 @@
+use wildmatch::WildMatch;
+
+use crate::{
+  data::{Subject, Timetable},
+  filter::{self, Filter},
+};
+
+struct RequireCourseFilter(WildMatch);
+
+pub fn try_parse(spec: &str) -> Option<Result<Box<dyn Filter>, String>> {
+  filter::parse_with_key(spec, "require_course", |value| {
+    Ok(RequireCourseFilter(WildMatch::new(value)))
+  })
+}
+
+impl Filter for RequireCourseFilter {
+  fn filter(&self, timetable: &Timetable) -> bool {
+    timetable
+      .courses
+      .iter()
+      .any(|course| self.0.matches(&course.code))
+  }
+
+  fn updates_courses(&self) -> bool {
+    true
+  }
+
+  fn update_courses(&self, subjects: &mut [Subject]) {
+    subjects
+      .iter_mut()
+      .flat_map(|subject| &mut subject.courses)
+      .filter(|one_of_course| {
+        one_of_course
+          .iter()
+          .any(|course| self.0.matches(&course.code))
+      })
+      .flatten()
+      .filter(|course| !self.0.matches(&course.code))
+      .for_each(|course| course.is_hidden_by_user = true);
+  }
+}
@@ -0,0 +1,23 @@
+use crate::{
+  data::Timetable,
+  filter::{self, Filter, SubjectConstraint},
+  text,
+};
+
+struct SiteFilter(SubjectConstraint);
+
+pub fn try_parse(spec: &str) -> Option<Result<Box<dyn Filter>, String>> {
+  filter::parse_with_key(spec, "site", |value| {
+    Ok(SiteFilter(SubjectConstraint::parse(value)?))
+  })
+}
+
+impl Filter for SiteFilter {
+  fn filter(&self, timetable: &Timetable) -> bool {
+    timetable
+      .courses
+      .iter()
+      .filter(|course| self.0.applies_to(course))
+      .all(|course| text::fuzzy_contains(&course.site, &self.0.value))
+  }
+}
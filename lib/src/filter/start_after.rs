@@ -0,0 +1,34 @@
+use chrono::{NaiveTime, Weekday};
+
+use crate::{
+  data::Timetable,
+  filter::{self, Filter},
+};
+
+struct StartAfterFilter {
+  pub weekday: Weekday,
+  pub start: NaiveTime,
+}
+
+pub fn try_parse(spec: &str) -> Option<Result<Box<dyn Filter>, String>> {
+  filter::parse_with_key(spec, "start_after", |value| {
+    let (weekday, start) = value
+      .split_once(',')
+      .ok_or(format!("Not enough comma seperated arguments: {value}"))?;
+    Ok(StartAfterFilter {
+      weekday: filter::parse_weekday(weekday)?,
+      start: filter::parse_time(start)?,
+    })
+  })
+}
+
+impl Filter for StartAfterFilter {
+  fn filter(&self, timetable: &Timetable) -> bool {
+    timetable
+      .courses
+      .iter()
+      .map(|course| &course.occurrence)
+      .filter(|occ| occ.weekday == self.weekday)
+      .all(|occ| occ.start_time >= self.start)
+  }
+}
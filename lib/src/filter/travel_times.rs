@@ -0,0 +1,81 @@
+use std::{collections::HashMap, fs};
+
+use chrono::Duration;
+use itertools::Itertools;
+
+use crate::{
+  data::Timetable,
+  filter::{self, Filter},
+};
+
+struct TravelTimesFilter(HashMap<(String, String), Duration>);
+
+pub fn try_parse(spec: &str) -> Option<Result<Box<dyn Filter>, String>> {
+  filter::parse_with_key(spec, "travel_times", |value| {
+    let csv = match inline_csv(value) {
+      Some(csv) => csv,
+      None => fs::read_to_string(value).map_err(|e| format!("Failed to read file {value}: {e}"))?,
+    };
+    Ok(TravelTimesFilter(parse_travel_times(&csv)?))
+  })
+}
+
+/// Checks the specification without reading the file.
+pub(crate) fn check_syntax(spec: &str) -> Option<Result<(), String>> {
+  let value = spec.strip_prefix("travel_times=")?;
+  Some(match inline_csv(value) {
+    Some(csv) => parse_travel_times(&csv).map(|_| ()),
+    None if value.is_empty() => Err("Missing file name".to_string()),
+    None => Ok(()),
+  })
+}
+
+/// The travel times given in the specification instead of a file: `csv:from,to,minutes;from,to,minutes`
+fn inline_csv(value: &str) -> Option<String> {
+  value
+    .strip_prefix("csv:")
+    .map(|rows| rows.replace(';', "\n"))
+}
+
+fn parse_travel_times(csv: &str) -> Result<HashMap<(String, String), Duration>, String> {
+  let mut travel_times = HashMap::new();
+  for line in csv.lines().map(str::trim) {
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+
+    let Some((from, to, minutes)) = line.split(',').map(str::trim).collect_tuple() else {
+      return Err(format!("Expected from,to,minutes: {line}"));
+    };
+    let minutes = minutes
+      .parse::<u32>()
+      .map_err(|_| format!("Invalid positive number: {minutes}"))?;
+    let travel_time = Duration::minutes(minutes.into());
+    // walking is assumed to take the same time in both directions
+    travel_times.insert((from.to_string(), to.to_string()), travel_time);
+    travel_times.insert((to.to_string(), from.to_string()), travel_time);
+  }
+
+  Ok(travel_times)
+}
+
+impl Filter for TravelTimesFilter {
+  fn filter(&self, timetable: &Timetable) -> bool {
+    timetable
+      .courses
+      .iter()
+      .chunk_by(|course| course.occurrence.weekday)
+      .into_iter()
+      .all(|(_, courses)| {
+        courses.tuple_windows().all(|(current, next)| {
+          let key = (current.building().to_string(), next.building().to_string());
+          match self.0.get(&key) {
+            Some(&travel_time) => {
+              next.occurrence.start_time - current.occurrence.end_time >= travel_time
+            }
+            None => true,
+          }
+        })
+      })
+  }
+}
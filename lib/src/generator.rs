@@ -0,0 +1,660 @@
+use std::{
+  collections::HashMap,
+  fmt::{self, Display, Formatter},
+};
+
+use chrono::NaiveTime;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::data::{Course, OneOfCourse, Subject, Timetable};
+
+/// States counted before the count is estimated instead, as it can grow exponentially.
+const MAX_COUNTING_STEPS: usize = 500_000;
+/// Random combinations an estimated count is based on.
+const ESTIMATE_SAMPLES: u32 = 10_000;
+const ESTIMATE_SEED: u64 = 0x2545_f491_4f6c_dd1d;
+/// Standard errors on each side of an estimate for a 95% confidence interval.
+const CONFIDENCE_Z: f64 = 1.96;
+
+/// Generates the timetables one by one, each combination of courses has an index,
+/// so the generation can be split into parts and continued later.
+/// The courses are copied, so the generator can be kept while the subjects change.
+pub struct TimetableGenerator {
+  /// `None` means the whole group is left out, only possible for optional groups.
+  one_of_courses: Vec<Vec<Option<Course>>>,
+}
+
+impl TimetableGenerator {
+  pub fn new(subjects: &[Subject]) -> TimetableGenerator {
+    let one_of_courses = subjects
+      .iter()
+      .flat_map(|subject| &subject.courses)
+      // a group hidden entirely leaves no timetables, only the groups without courses are skipped
+      .filter(|one_of_course| one_of_course.iter().any(|course| !course.is_deleted))
+      .map(choices)
+      .collect();
+
+    TimetableGenerator { one_of_courses }
+  }
+
+  /// Count of all the combinations, including the clashing ones, `None` when it doesn't fit in a `u64`.
+  pub fn combination_count(&self) -> Option<u64> {
+    self
+      .one_of_courses
+      .iter()
+      .try_fold(1u64, |count, one_of_course| {
+        count.checked_mul(one_of_course.len() as u64)
+      })
+  }
+
+  /// The timetable of the combination at `index`,
+  /// `None` when its courses clash or it leaves out an optional course that would fit.
+  /// A group hidden entirely has no combinations, so there is no timetable at any index.
+  pub fn timetable_at(&self, index: u64) -> Option<Timetable<'_>> {
+    let mut remaining = index;
+    let mut courses = vec![];
+    let mut skipped_groups = vec![];
+    for one_of_course in &self.one_of_courses {
+      let choice = remaining.checked_rem(one_of_course.len() as u64)?;
+      remaining /= one_of_course.len() as u64;
+      match &one_of_course[choice as usize] {
+        Some(course) => courses.push(course),
+        None => skipped_groups.push(one_of_course),
+      }
+    }
+
+    // by the end too, so clashing courses are adjacent even when a course has no duration
+    let courses = courses
+      .into_iter()
+      .sorted_by_key(|course| {
+        let occ = &course.occurrence;
+        (occ.weekday as u8, occ.start_time, occ.end_time)
+      })
+      .collect_vec();
+
+    let is_overlapping = courses
+      .iter()
+      .tuple_windows()
+      .any(|(course, next)| clashes(course, next));
+    if is_overlapping {
+      return None;
+    }
+
+    // optional courses are only left out when none of them fit
+    let skips_fitting_course = skipped_groups
+      .iter()
+      .flat_map(|one_of_course| one_of_course.iter().flatten())
+      .any(|optional| !courses.iter().any(|course| clashes(optional, course)));
+    if skips_fitting_course {
+      return None;
+    }
+
+    Some(Timetable::new(index, courses))
+  }
+
+  /// Count of the combinations `timetable_at` returns a timetable for, without going through them.
+  /// It's estimated when counting exactly would take too long.
+  pub fn timetable_count(&self) -> TimetableCount {
+    let counter = TimetableCounter::new(&self.one_of_courses);
+    match counter.count(MAX_COUNTING_STEPS) {
+      Some(count) => TimetableCount::Exact(count),
+      None => TimetableCount::Estimate(counter.estimate(ESTIMATE_SAMPLES)),
+    }
+  }
+}
+
+/// Count of the timetables without clashes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimetableCount {
+  Exact(u64),
+  /// Estimated from random combinations, as there were too many to count exactly.
+  Estimate(Estimate),
+}
+
+/// A count estimated from random combinations, with its 95% confidence interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Estimate {
+  pub value: u64,
+  pub low: u64,
+  pub high: u64,
+  /// The random combinations the estimate is based on.
+  pub samples: u32,
+}
+
+impl TimetableCount {
+  pub fn value(self) -> u64 {
+    match self {
+      TimetableCount::Exact(count) => count,
+      TimetableCount::Estimate(estimate) => estimate.value,
+    }
+  }
+}
+
+impl Display for TimetableCount {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      TimetableCount::Exact(count) => write!(f, "{count}"),
+      TimetableCount::Estimate(estimate) => write!(
+        f,
+        "approximately {} (95% between {} and {}, estimated from {} samples)",
+        estimate.value, estimate.low, estimate.high, estimate.samples
+      ),
+    }
+  }
+}
+
+/// Counts the timetables going through the courses in order of time, choosing or leaving out each.
+/// Courses only clash on the same day, so the combinations so far only matter through which of the
+/// groups with courses still ahead have a chosen course, and when the day's last chosen course ends.
+struct TimetableCounter<'a> {
+  /// The courses sorted by time, with the index of their group.
+  courses: Vec<(&'a Course, usize)>,
+  /// Course indices of each group.
+  groups: Vec<Vec<usize>>,
+  is_optional: Vec<bool>,
+}
+
+/// The combinations of the courses before the current one, that are counted together.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct CountingState {
+  /// Groups with courses ahead that have a chosen course.
+  chosen: IndexSet,
+  /// Optional groups with courses ahead that have a course that fits, so they can't be left out.
+  must_choose: IndexSet,
+  /// End of the last chosen course of the day.
+  day_end: NaiveTime,
+  /// Ends and groups of the left out optional courses, that can still clash with a later course.
+  pending: Vec<(NaiveTime, usize)>,
+}
+
+impl<'a> TimetableCounter<'a> {
+  fn new(one_of_courses: &'a [Vec<Option<Course>>]) -> TimetableCounter<'a> {
+    let courses = one_of_courses
+      .iter()
+      .enumerate()
+      .flat_map(|(group, one_of_course)| {
+        one_of_course
+          .iter()
+          .flatten()
+          .map(move |course| (course, group))
+      })
+      // by the end too, so clashing courses are adjacent even when a course has no duration
+      .sorted_by_key(|(course, _)| {
+        let occ = &course.occurrence;
+        (occ.weekday as u8, occ.start_time, occ.end_time)
+      })
+      .collect_vec();
+    let mut groups = vec![vec![]; one_of_courses.len()];
+    for (i, &(_, group)) in courses.iter().enumerate() {
+      groups[group].push(i);
+    }
+    let is_optional = one_of_courses
+      .iter()
+      .map(|one_of_course| one_of_course.contains(&None))
+      .collect();
+
+    TimetableCounter {
+      courses,
+      groups,
+      is_optional,
+    }
+  }
+
+  /// The exact count, `None` when it takes more than `max_steps` states.
+  fn count(&self, max_steps: usize) -> Option<u64> {
+    // a required group without a course to choose leaves no timetables
+    let has_empty_group = self
+      .groups
+      .iter()
+      .zip(&self.is_optional)
+      .any(|(courses, &is_optional)| courses.is_empty() && !is_optional);
+    if has_empty_group {
+      return Some(0);
+    }
+
+    let start = CountingState {
+      chosen: IndexSet::new(self.groups.len()),
+      must_choose: IndexSet::new(self.groups.len()),
+      day_end: NaiveTime::MIN,
+      pending: vec![],
+    };
+    let mut states = HashMap::from([(start, 1u64)]);
+    let mut steps = 0;
+    for (i, &(course, group)) in self.courses.iter().enumerate() {
+      let occ = &course.occurrence;
+      let is_new_day = i > 0 && self.courses[i - 1].0.occurrence.weekday != occ.weekday;
+      let mut next_states = HashMap::new();
+      let mut add = |state: Option<CountingState>, count: u64| {
+        if let Some(state) = state {
+          let total: &mut u64 = next_states.entry(state).or_default();
+          *total = total.saturating_add(count);
+        }
+      };
+      for (state, count) in states {
+        steps += 1;
+        if steps > max_steps {
+          return None;
+        }
+        let Some(mut state) = self.expire_pending(state, occ.start_time, is_new_day, i) else {
+          continue;
+        };
+
+        let is_blocked = state.day_end > occ.start_time;
+        if !is_blocked && !state.chosen.contains(group) {
+          let mut chosen = state.clone();
+          chosen.chosen.insert(group);
+          chosen.must_choose.remove(group);
+          // the left out courses ending after the start clash with it
+          chosen
+            .pending
+            .retain(|&(end, other)| other != group && end <= occ.start_time);
+          chosen.day_end = chosen.day_end.max(occ.end_time);
+          add(self.close_group(chosen, i), count);
+        }
+
+        if !is_blocked && self.is_optional[group] && !state.chosen.contains(group) {
+          let position = state
+            .pending
+            .partition_point(|&pending| pending < (occ.end_time, group));
+          state.pending.insert(position, (occ.end_time, group));
+        }
+        add(self.close_group(state, i), count);
+      }
+      states = next_states;
+    }
+
+    // the courses still pending at the end fit into the timetable
+    Some(
+      states
+        .into_iter()
+        .filter(|(state, _)| state.pending.is_empty())
+        .fold(0, |total, (_, count)| total.saturating_add(count)),
+    )
+  }
+
+  /// Left out courses ending before the current course can't clash with a later one,
+  /// so their group has to have a chosen course. `None` when the group can't have one anymore.
+  fn expire_pending(
+    &self,
+    mut state: CountingState,
+    start: NaiveTime,
+    is_new_day: bool,
+    current: usize,
+  ) -> Option<CountingState> {
+    if is_new_day {
+      state.day_end = NaiveTime::MIN;
+    }
+    let (expired, pending): (Vec<_>, Vec<_>) = state
+      .pending
+      .into_iter()
+      .partition(|&(end, _)| is_new_day || end <= start);
+    state.pending = pending;
+    for (_, group) in expired {
+      if self.groups[group].last() < Some(&current) {
+        return None;
+      }
+      state.must_choose.insert(group);
+    }
+
+    Some(state)
+  }
+
+  /// Checks the group after its last course, it's left out of the state afterwards,
+  /// so the states only differing in it are counted together.
+  fn close_group(&self, mut state: CountingState, current: usize) -> Option<CountingState> {
+    let group = self.courses[current].1;
+    if self.groups[group].last() != Some(&current) {
+      return Some(state);
+    }
+    if !state.chosen.contains(group)
+      && (!self.is_optional[group] || state.must_choose.contains(group))
+    {
+      return None;
+    }
+    state.chosen.remove(group);
+    state.must_choose.remove(group);
+
+    Some(state)
+  }
+
+  /// Average of random choices of a course for each group, weighted by the count of the courses
+  /// that could have been chosen, so the average is the count of the timetables.
+  /// The confidence interval assumes the average is normally distributed.
+  fn estimate(&self, samples: u32) -> Estimate {
+    let clashing = self
+      .courses
+      .iter()
+      .map(|(course, _)| {
+        let mut set = IndexSet::new(self.courses.len());
+        for (i, (other, _)) in self.courses.iter().enumerate() {
+          if clashes(course, other) {
+            set.insert(i);
+          }
+        }
+        set
+      })
+      .collect_vec();
+
+    let mut random = Random(ESTIMATE_SEED);
+    let weights = (0..samples)
+      .map(|_| self.sample(&clashing, &mut random))
+      .collect_vec();
+    let count = samples as f64;
+    let mean = weights.iter().sum::<f64>() / count;
+    let variance = weights
+      .iter()
+      .map(|weight| (weight - mean).powi(2))
+      .sum::<f64>()
+      / (count - 1.0).max(1.0);
+    let margin = CONFIDENCE_Z * (variance / count).sqrt();
+    // a sampled timetable proves there is at least one
+    let min = match weights.iter().any(|&weight| weight > 0.0) {
+      true => 1,
+      false => 0,
+    };
+    Estimate {
+      value: (mean.round() as u64).max(min),
+      low: ((mean - margin).round().max(0.0) as u64).max(min),
+      high: (mean + margin).round() as u64,
+      samples,
+    }
+  }
+
+  /// The group with the fewest courses left to choose from is chosen next,
+  /// so the combinations that can't be completed are found early.
+  fn sample(&self, clashing: &[IndexSet], random: &mut Random) -> f64 {
+    let mut blocked = IndexSet::new(self.courses.len());
+    let mut remaining_groups = (0..self.groups.len()).collect_vec();
+    let mut skipped_groups = vec![];
+    let mut weight = 1.0;
+    while !remaining_groups.is_empty() {
+      let (position, choices) = remaining_groups
+        .iter()
+        .map(|&group| {
+          self.groups[group]
+            .iter()
+            .filter(|&&course| !blocked.contains(course))
+            .map(Some)
+            .chain(self.is_optional[group].then_some(None))
+            .collect_vec()
+        })
+        .enumerate()
+        .min_by_key(|(_, choices)| choices.len())
+        .unwrap();
+      if choices.is_empty() {
+        return 0.0;
+      }
+      let group = remaining_groups.swap_remove(position);
+      weight *= choices.len() as f64;
+      match choices[random.below(choices.len())] {
+        Some(&course) => blocked.union_with(&clashing[course]),
+        None => skipped_groups.push(group),
+      }
+    }
+
+    let skips_fitting_course = skipped_groups
+      .iter()
+      .flat_map(|&group| &self.groups[group])
+      .any(|&course| !blocked.contains(course));
+    match skips_fitting_course {
+      true => 0.0,
+      false => weight,
+    }
+  }
+}
+
+/// A set of indices.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct IndexSet(Vec<u64>);
+
+impl IndexSet {
+  fn new(len: usize) -> IndexSet {
+    IndexSet(vec![0; len.div_ceil(64)])
+  }
+
+  fn insert(&mut self, index: usize) {
+    self.0[index / 64] |= 1 << (index % 64);
+  }
+
+  fn remove(&mut self, index: usize) {
+    self.0[index / 64] &= !(1 << (index % 64));
+  }
+
+  fn contains(&self, index: usize) -> bool {
+    self.0[index / 64] & (1 << (index % 64)) != 0
+  }
+
+  fn union_with(&mut self, other: &IndexSet) {
+    for (bits, other) in self.0.iter_mut().zip(&other.0) {
+      *bits |= other;
+    }
+  }
+}
+
+/// A xorshift generator, the estimates are the same every time.
+struct Random(u64);
+
+impl Random {
+  fn below(&mut self, n: usize) -> usize {
+    self.0 ^= self.0 << 13;
+    self.0 ^= self.0 >> 7;
+    self.0 ^= self.0 << 17;
+    (self.0 % n as u64) as usize
+  }
+}
+
+fn clashes(course: &Course, other: &Course) -> bool {
+  let (occ, other) = (&course.occurrence, &other.occurrence);
+  occ.weekday == other.weekday && occ.start_time < other.end_time && other.start_time < occ.end_time
+}
+
+fn choices(one_of_course: &OneOfCourse) -> Vec<Option<Course>> {
+  let mut courses = one_of_course
+    .iter()
+    .filter(|course| !course.is_ignored())
+    .cloned()
+    .map(Some)
+    .collect_vec();
+  let is_optional = one_of_course
+    .iter()
+    .filter(|course| !course.is_deleted)
+    .all(|course| course.is_optional);
+  if is_optional {
+    // leaving out the whole group is also a choice
+    courses.push(None);
+  }
+
+  courses
+}
+
+/// All the timetables of the generator, an error when there are too many combinations to go through.
+pub fn generate_timetables(generator: &TimetableGenerator) -> Result<Vec<Timetable<'_>>, String> {
+  let count = generator
+    .combination_count()
+    .ok_or("Too many combinations to generate, hide some courses")?;
+  Ok(
+    (0..count)
+      .filter_map(|index| generator.timetable_at(index))
+      .collect(),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use chrono::{NaiveTime, Weekday};
+
+  use super::{Random, TimetableCount, TimetableCounter, TimetableGenerator, ESTIMATE_SAMPLES};
+  use crate::data::{Course, CourseType, Enrollment, Occurrence, Subject};
+
+  fn course(code: &str, weekday: Weekday, start: &str, end: &str) -> Course {
+    let time = |time| NaiveTime::parse_from_str(time, "%H:%M").unwrap();
+    Course::new(
+      "Subject".to_string(),
+      code.to_string(),
+      CourseType::Practice,
+      Enrollment::default(),
+      String::new(),
+      String::new(),
+      String::new(),
+      String::new(),
+      String::new(),
+      String::new(),
+      Occurrence {
+        weeks: None,
+        weekday,
+        start_time: time(start),
+        end_time: time(end),
+      },
+    )
+  }
+
+  fn optional(mut course: Course) -> Course {
+    course.is_optional = true;
+    course
+  }
+
+  fn subject(courses: Vec<Vec<Course>>) -> Subject {
+    Subject {
+      name: "Subject".to_string(),
+      courses,
+    }
+  }
+
+  /// Courses on few days and times, so they often clash,
+  /// with optional groups, hidden courses and courses without a time like the ones added by hand.
+  fn random_subjects(
+    random: &mut Random,
+    weekdays: &[Weekday],
+    max_courses: usize,
+  ) -> Vec<Subject> {
+    let times = [
+      "08:00", "08:30", "09:00", "09:30", "10:00", "10:30", "11:00",
+    ];
+    (0..1 + random.below(3))
+      .map(|_| {
+        let groups = (0..1 + random.below(2))
+          .map(|_| {
+            let is_optional = random.below(4) == 0;
+            (0..1 + random.below(max_courses))
+              .map(|i| {
+                let code = format!("C{i}");
+                let mut course = match random.below(5) {
+                  0 => course(&code, Weekday::Mon, "00:00", "00:00"),
+                  _ => {
+                    let start = random.below(times.len() - 1);
+                    let end = start + random.below(times.len() - start);
+                    let weekday = weekdays[random.below(weekdays.len())];
+                    course(&code, weekday, times[start], times[end])
+                  }
+                };
+                course.is_optional = is_optional;
+                course.is_hidden_by_user = random.below(8) == 0;
+                course
+              })
+              .collect()
+          })
+          .collect();
+        subject(groups)
+      })
+      .collect()
+  }
+
+  fn brute_force_count(generator: &TimetableGenerator) -> u64 {
+    let combinations = generator.combination_count().unwrap();
+    (0..combinations)
+      .filter(|&index| generator.timetable_at(index).is_some())
+      .count() as u64
+  }
+
+  #[test]
+  fn count_matches_brute_force() {
+    let mut random = Random(1);
+    for _ in 0..500 {
+      let subjects = random_subjects(&mut random, &[Weekday::Mon, Weekday::Tue], 3);
+      let generator = TimetableGenerator::new(&subjects);
+      assert_eq!(
+        generator.timetable_count(),
+        TimetableCount::Exact(brute_force_count(&generator))
+      );
+    }
+  }
+
+  #[test]
+  fn optional_group_is_left_out_only_when_none_of_its_courses_fit() {
+    let required = vec![course("EA", Weekday::Mon, "08:00", "09:30")];
+    let clashing = vec![
+      optional(course("GY_01", Weekday::Mon, "08:00", "09:30")),
+      optional(course("GY_02", Weekday::Mon, "09:00", "10:00")),
+    ];
+    let generator = TimetableGenerator::new(&[subject(vec![required.clone(), clashing.clone()])]);
+    assert_eq!(generator.timetable_count(), TimetableCount::Exact(1));
+    assert_eq!(brute_force_count(&generator), 1);
+
+    let mut fitting = clashing;
+    fitting.push(optional(course("GY_03", Weekday::Mon, "09:30", "11:00")));
+    let generator = TimetableGenerator::new(&[subject(vec![required, fitting])]);
+    assert_eq!(generator.timetable_count(), TimetableCount::Exact(1));
+    let timetable = (0..generator.combination_count().unwrap())
+      .find_map(|index| generator.timetable_at(index))
+      .unwrap();
+    assert_eq!(timetable.courses[1].code, "GY_03");
+  }
+
+  #[test]
+  fn courses_without_a_duration_only_clash_inside_other_courses() {
+    let subjects = [subject(vec![
+      vec![course("EA", Weekday::Mon, "00:00", "00:00")],
+      vec![course("GY", Weekday::Mon, "00:00", "00:00")],
+      vec![course("LA", Weekday::Mon, "08:00", "09:30")],
+      vec![
+        course("EL_01", Weekday::Mon, "08:00", "08:00"),
+        course("EL_02", Weekday::Mon, "09:00", "09:00"),
+        course("EL_03", Weekday::Mon, "09:30", "09:30"),
+      ],
+    ])];
+    let generator = TimetableGenerator::new(&subjects);
+    assert_eq!(generator.timetable_count(), TimetableCount::Exact(2));
+    assert_eq!(brute_force_count(&generator), 2);
+  }
+
+  #[test]
+  fn group_hidden_entirely_leaves_no_timetables() {
+    let mut hidden = course("GY_01", Weekday::Tue, "08:00", "09:30");
+    hidden.is_hidden_by_user = true;
+    let subjects = [subject(vec![
+      vec![course("EA", Weekday::Mon, "08:00", "09:30")],
+      vec![hidden],
+    ])];
+    let generator = TimetableGenerator::new(&subjects);
+    assert_eq!(generator.combination_count(), Some(0));
+    assert_eq!(generator.timetable_count(), TimetableCount::Exact(0));
+    // indices generated before the group was hidden
+    assert!((0..3).all(|index| generator.timetable_at(index).is_none()));
+  }
+
+  #[test]
+  fn estimate_is_close_to_the_exact_count() {
+    let mut random = Random(7);
+    let weekdays = [Weekday::Mon, Weekday::Tue, Weekday::Wed];
+    let subjects = (0..3)
+      .flat_map(|_| random_subjects(&mut random, &weekdays, 6))
+      .collect::<Vec<_>>();
+    let generator = TimetableGenerator::new(&subjects);
+    let counter = TimetableCounter::new(&generator.one_of_courses);
+
+    let exact = counter.count(usize::MAX).unwrap();
+    let estimate = counter.estimate(ESTIMATE_SAMPLES);
+    assert!(exact > 100);
+    assert!(
+      estimate.value.abs_diff(exact) * 10 < exact,
+      "{estimate:?} is far from {exact}"
+    );
+    assert!(
+      (estimate.low..=estimate.high).contains(&exact),
+      "{exact} is outside {estimate:?}"
+    );
+    assert!(estimate.low < estimate.value && estimate.value < estimate.high);
+    assert_eq!(estimate.samples, ESTIMATE_SAMPLES);
+  }
+}
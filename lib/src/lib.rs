@@ -1,3 +1,10 @@
+pub mod bottleneck;
 pub mod data;
 pub mod excel_parser;
+pub mod filter;
+pub mod generator;
+#[cfg(feature = "neptun")]
+pub mod neptun;
+pub mod share;
 pub mod stats;
+pub mod text;
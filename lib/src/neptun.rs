@@ -0,0 +1,192 @@
+use std::time::Duration;
+
+use serde::{de::DeserializeOwned, Deserialize, Deserializer};
+use serde_json::{json, Value};
+
+use crate::{
+  data::{Course, Enrollment, Subject},
+  excel_parser,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Client of the mobile service of Neptun, each university runs its own instance,
+/// so the base URL is the address of the service, e.g. `https://neptun.example.edu/hallgato/MobileService.svc`.
+/// Every request is a JSON POST carrying the credentials, there is no session.
+pub struct NeptunClient {
+  agent: ureq::Agent,
+  base_url: String,
+  user_login: String,
+  password: String,
+  training_id: u64,
+}
+
+/// A subject the student registered to in the current term.
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct RegisteredSubject {
+  pub subject_id: u64,
+  pub subject_code: String,
+  pub subject_name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Training {
+  id: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TrainingsResponse {
+  #[serde(default, deserialize_with = "null_as_default")]
+  training_list: Vec<Training>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SubjectsResponse {
+  #[serde(default, deserialize_with = "null_as_default")]
+  added_subjects_list: Vec<RegisteredSubject>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CoursesResponse {
+  #[serde(default, deserialize_with = "null_as_default")]
+  course_list: Vec<NeptunCourse>,
+}
+
+/// A course as Neptun sends it, the time table info has the same format as in the excel export.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct NeptunCourse {
+  course_code: String,
+  course_type: String,
+  course_time_table_info: String,
+  #[serde(default, deserialize_with = "null_as_default")]
+  course_tutor: String,
+  #[serde(default, deserialize_with = "null_as_default")]
+  course_language: String,
+  #[serde(default, deserialize_with = "null_as_default")]
+  course_site: String,
+  #[serde(default, deserialize_with = "null_as_default")]
+  course_comment: String,
+  #[serde(default, deserialize_with = "null_as_default")]
+  course_enrolled: u32,
+  #[serde(default, deserialize_with = "null_as_default")]
+  course_queue: u32,
+  #[serde(default, deserialize_with = "null_as_default")]
+  course_limit: u32,
+}
+
+impl NeptunClient {
+  /// Checks the credentials by fetching the trainings of the student, the first one is used.
+  pub fn login(base_url: &str, user_login: &str, password: &str) -> Result<NeptunClient, String> {
+    let mut client = NeptunClient {
+      agent: ureq::AgentBuilder::new().timeout(TIMEOUT).build(),
+      base_url: base_url.trim_end_matches('/').to_string(),
+      user_login: user_login.to_string(),
+      password: password.to_string(),
+      training_id: 0,
+    };
+    let trainings: TrainingsResponse = client.post("GetTrainings", json!({}))?;
+    let Some(training) = trainings.training_list.first() else {
+      return Err("The student has no trainings".to_string());
+    };
+    client.training_id = training.id;
+
+    Ok(client)
+  }
+
+  pub fn registered_subjects(&self) -> Result<Vec<RegisteredSubject>, String> {
+    let subjects: SubjectsResponse = self.post(
+      "GetAddedSubjects",
+      json!({ "TrainingId": self.training_id }),
+    )?;
+    Ok(subjects.added_subjects_list)
+  }
+
+  /// Fetches the courses of the subject, grouped by their course type like the excel parser does.
+  pub fn subject(&self, registered: &RegisteredSubject) -> Result<Subject, String> {
+    let courses: CoursesResponse = self.post(
+      "GetCourses",
+      json!({ "TrainingId": self.training_id, "SubjectId": registered.subject_id }),
+    )?;
+
+    let mut subject = Subject {
+      name: registered.subject_name.clone(),
+      courses: vec![],
+    };
+    for course in courses.course_list {
+      let code = course.course_code.clone();
+      let course = parse_course(&registered.subject_name, course)
+        .map_err(|e| format!("{}, course {code}: {e}", registered.subject_name))?;
+      subject.add_course(course);
+    }
+
+    Ok(subject)
+  }
+
+  /// Fetches the courses of all the registered subjects.
+  pub fn subjects(&self) -> Result<Vec<Subject>, String> {
+    self
+      .registered_subjects()?
+      .iter()
+      .map(|registered| self.subject(registered))
+      .collect()
+  }
+
+  fn post<T: DeserializeOwned>(&self, method: &str, mut body: Value) -> Result<T, String> {
+    body["UserLogin"] = self.user_login.clone().into();
+    body["Password"] = self.password.clone().into();
+
+    let url = format!("{}/{method}", self.base_url);
+    let response: Value = self
+      .agent
+      .post(&url)
+      .send_json(body)
+      .map_err(|e| format!("Request failed: {e}"))?
+      .into_json()
+      .map_err(|e| format!("Invalid response from {url}: {e}"))?;
+    if let Some(message) = response["ErrorMessage"].as_str() {
+      return Err(format!("Neptun: {message}"));
+    }
+
+    serde_json::from_value(response).map_err(|e| format!("Invalid response from {url}: {e}"))
+  }
+}
+
+/// Neptun sends `null` instead of an empty list or text.
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+  D: Deserializer<'de>,
+  T: Default + Deserialize<'de>,
+{
+  Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+fn parse_course(subject_name: &str, course: NeptunCourse) -> Result<Course, String> {
+  let course_type = excel_parser::parse_course_type(&course.course_type)?;
+  let (occurrence, location) =
+    excel_parser::parse_occurrence_and_location(course.course_time_table_info)?;
+  let enrollment = Enrollment {
+    people_joined: course.course_enrolled,
+    people_queue: course.course_queue,
+    people_limit: course.course_limit,
+  };
+
+  Ok(Course::new(
+    subject_name.to_string(),
+    course.course_code,
+    course_type,
+    enrollment,
+    location,
+    course.course_tutor,
+    course.course_language,
+    course.course_site,
+    course.course_comment,
+    String::new(),
+    occurrence,
+  ))
+}
@@ -0,0 +1,70 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use miniz_oxide::{deflate::compress_to_vec, inflate::decompress_to_vec_with_limit};
+use serde::{Deserialize, Serialize};
+
+/// The links come from anyone, a small one could decompress to more than the page can hold.
+const MAX_DECOMPRESSED_SIZE: usize = 1 << 20;
+
+/// A timetable and the filters it was chosen with, small enough to be shared in a link.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SharedTimetable {
+  /// The subject names and the codes of the courses, codes are only unique within a subject.
+  pub courses: Vec<(String, String)>,
+  pub filters: Vec<String>,
+}
+
+/// Compressed and URL-safe base64 encoded, so it can be used as a URL fragment.
+pub fn encode(shared: &SharedTimetable) -> String {
+  let json = serde_json::to_vec(shared).unwrap();
+  URL_SAFE_NO_PAD.encode(compress_to_vec(&json, 9))
+}
+
+pub fn decode(encoded: &str) -> Result<SharedTimetable, String> {
+  let compressed = URL_SAFE_NO_PAD
+    .decode(encoded)
+    .map_err(|e| format!("Invalid shared timetable: {e}"))?;
+  let json = decompress_to_vec_with_limit(&compressed, MAX_DECOMPRESSED_SIZE)
+    .map_err(|e| format!("Invalid shared timetable: {e:?}"))?;
+  serde_json::from_slice(&json).map_err(|e| format!("Invalid shared timetable: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+  use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+  use miniz_oxide::deflate::compress_to_vec;
+
+  use super::{decode, encode, SharedTimetable, MAX_DECOMPRESSED_SIZE};
+
+  #[test]
+  fn decoding_gives_back_the_encoded_timetable() {
+    let shared = SharedTimetable {
+      courses: vec![
+        ("Physics".to_string(), "GY_01".to_string()),
+        ("Chemistry".to_string(), "GY_01".to_string()),
+      ],
+      filters: vec![
+        "min_start_time=10:00".to_string(),
+        "travel_times=csv:BA,BC,10".to_string(),
+      ],
+    };
+    let encoded = encode(&shared);
+    assert!(encoded
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    assert_eq!(decode(&encoded), Ok(shared));
+  }
+
+  #[test]
+  fn decompressing_is_limited() {
+    let zeros = vec![0; MAX_DECOMPRESSED_SIZE + 1];
+    let encoded = URL_SAFE_NO_PAD.encode(compress_to_vec(&zeros, 9));
+    assert!(encoded.len() < 10_000);
+    assert!(decode(&encoded).is_err());
+  }
+
+  #[test]
+  fn invalid_links_are_rejected() {
+    assert!(decode("not base64!").is_err());
+    assert!(decode(&URL_SAFE_NO_PAD.encode(b"not deflate")).is_err());
+  }
+}
@@ -1,4 +1,14 @@
-use crate::data::{OneOfCourse, Subject};
+use std::collections::BTreeMap;
+
+use chrono::{Duration, NaiveTime};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  data::{Course, OneOfCourse, Subject, Timetable},
+  filter::{self, Filter},
+  generator::{TimetableCount, TimetableGenerator},
+};
 
 fn count_one_of_courses(courses: &OneOfCourse) -> u64 {
   courses.iter().filter(|&c| !c.is_ignored()).count() as u64
@@ -31,16 +41,225 @@ pub fn count_course_per_timetable(subjects: &[Subject]) -> u64 {
     .sum()
 }
 
-pub fn count_all_timetables(subjects: &[Subject]) -> u64 {
+/// Count of all the combinations of courses, `None` when it doesn't fit in a `u64`.
+pub fn count_all_timetables(subjects: &[Subject]) -> Option<u64> {
   subjects
     .iter()
-    .map(|subject| {
-      subject
-        .courses
-        .iter()
-        .map(count_one_of_courses)
-        .filter(|&count| count > 0)
-        .product::<u64>()
+    .flat_map(|subject| &subject.courses)
+    .filter(|one_of_courses| one_of_courses.iter().any(|course| !course.is_deleted))
+    .map(count_one_of_courses)
+    .try_fold(1u64, |count, group_count| count.checked_mul(group_count))
+}
+
+/// Statistics of the generated timetables,
+/// the histograms count the timetables by a property of the whole week.
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct TimetableStats {
+  pub count: usize,
+  /// Free days from monday to friday.
+  pub free_workdays: BTreeMap<usize, usize>,
+  pub earliest_start: BTreeMap<NaiveTime, usize>,
+  pub latest_end: BTreeMap<NaiveTime, usize>,
+  /// Sum of the gaps between the courses of each day, in whole hours.
+  /// A day split into odd and even weeks counts with the week that has more gaps.
+  pub total_gap_hours: BTreeMap<i64, usize>,
+  /// How many of the timetables each course is in, by subject name and course code.
+  pub course_frequency: BTreeMap<(String, String), usize>,
+}
+
+impl TimetableStats {
+  /// Percentage of the timetables the course is in.
+  pub fn course_percentage(&self, subject_name: &str, code: &str) -> f64 {
+    let key = (subject_name.to_string(), code.to_string());
+    match self.count {
+      0 => 0.0,
+      count => self.course_frequency.get(&key).copied().unwrap_or(0) as f64 * 100.0 / count as f64,
+    }
+  }
+
+  /// Adds the timetable to the statistics, so they can be collected while the timetables are generated.
+  pub fn add(&mut self, timetable: &Timetable) {
+    self.count += 1;
+    let busy_workdays = filter::busy_weekdays(timetable, false)
+      .into_iter()
+      .filter(|weekday| weekday.num_days_from_monday() < 5)
+      .count();
+    *self.free_workdays.entry(5 - busy_workdays).or_default() += 1;
+
+    // courses without a time are left out, they are at midnight with no duration
+    let occurrences = timetable
+      .courses
+      .iter()
+      .map(|course| &course.occurrence)
+      .filter(|occ| occ.duration() > Duration::zero())
+      .collect_vec();
+    if let Some(start) = occurrences.iter().map(|occ| occ.start_time).min() {
+      *self.earliest_start.entry(start).or_default() += 1;
+    }
+    if let Some(end) = occurrences.iter().map(|occ| occ.end_time).max() {
+      *self.latest_end.entry(end).or_default() += 1;
+    }
+
+    let total_gap = filter::courses_per_weekday(timetable)
+      .iter()
+      .filter_map(|weeks| weeks.iter().map(|courses| day_gap(courses)).max())
+      .fold(Duration::zero(), |total, gap| total + gap);
+    *self
+      .total_gap_hours
+      .entry(total_gap.num_hours())
+      .or_default() += 1;
+
+    for course in &timetable.courses {
+      let key = (course.subject_name.clone(), course.code.clone());
+      *self.course_frequency.entry(key).or_default() += 1;
+    }
+  }
+}
+
+fn day_gap(courses: &[&Course]) -> Duration {
+  courses
+    .iter()
+    .tuple_windows()
+    .map(|(current, next)| next.occurrence.start_time - current.occurrence.end_time)
+    .filter(|gap| *gap > Duration::zero())
+    .fold(Duration::zero(), |total, gap| total + gap)
+}
+
+pub fn timetable_stats(timetables: &[Timetable]) -> TimetableStats {
+  let mut stats = TimetableStats::default();
+  for timetable in timetables {
+    stats.add(timetable);
+  }
+
+  stats
+}
+
+/// How many of the filters the timetable passes before one leaves it out.
+pub fn passed_filter_count(timetable: &Timetable, filters: &[&dyn Filter]) -> usize {
+  filters
+    .iter()
+    .take_while(|filter| filter.filter(timetable))
+    .count()
+}
+
+/// The count of timetables without clashes left after each filter that updates the courses,
+/// with the index of the filter. These filters take effect in order before the timetables are generated,
+/// so the timetables they leave are counted instead of checked one by one.
+pub fn course_filter_funnel(
+  subjects: &[Subject],
+  filters: &[&dyn Filter],
+) -> Vec<(usize, TimetableCount)> {
+  let mut subjects = subjects.to_vec();
+  filters
+    .iter()
+    .enumerate()
+    .filter(|(_, filter)| filter.updates_courses())
+    .map(|(i, filter)| {
+      filter.update_courses(&mut subjects);
+      (i, TimetableGenerator::new(&subjects).timetable_count())
     })
-    .product()
+    .collect()
+}
+
+/// The count of timetables left after each filter, each filter narrows down the previous ones.
+/// The timetables are generated after the courses were updated, so the filters updating the courses
+/// leave them all, see `course_filter_funnel`.
+pub fn filter_funnel(timetables: &[Timetable], filters: &[&dyn Filter]) -> Vec<usize> {
+  let mut funnel = vec![0; filters.len()];
+  for timetable in timetables {
+    for count in &mut funnel[..passed_filter_count(timetable, filters)] {
+      *count += 1;
+    }
+  }
+
+  funnel
+}
+
+#[cfg(test)]
+mod tests {
+  use chrono::{NaiveTime, Weekday};
+
+  use super::{course_filter_funnel, filter_funnel, TimetableStats};
+  use crate::{
+    data::{Course, CourseType, Enrollment, Occurrence, Subject, Timetable, Weeks},
+    filter,
+    generator::{self, TimetableCount, TimetableGenerator},
+  };
+
+  fn course(subject_name: &str, code: &str, weeks: Weeks, start: u32, end: u32) -> Course {
+    Course::new(
+      subject_name.to_string(),
+      code.to_string(),
+      CourseType::Practice,
+      Enrollment::default(),
+      String::new(),
+      String::new(),
+      String::new(),
+      String::new(),
+      String::new(),
+      String::new(),
+      Occurrence {
+        weeks: Some(weeks),
+        weekday: Weekday::Mon,
+        start_time: NaiveTime::from_hms_opt(start, 0, 0).unwrap(),
+        end_time: NaiveTime::from_hms_opt(end, 0, 0).unwrap(),
+      },
+    )
+  }
+
+  #[test]
+  fn split_day_gaps_are_counted_once() {
+    // two hours of gap on odd weeks, one on even weeks
+    let courses = [
+      course("Physics", "EA", Weeks::Every, 8, 10),
+      course("Physics", "GY_01", Weeks::Odd, 12, 13),
+      course("Physics", "GY_02", Weeks::Even, 11, 12),
+    ];
+    let mut stats = TimetableStats::default();
+    stats.add(&Timetable::new(0, courses.iter().collect()));
+
+    assert_eq!(
+      stats.total_gap_hours.into_iter().collect::<Vec<_>>(),
+      [(2, 1)]
+    );
+  }
+
+  #[test]
+  fn course_frequency_tells_subjects_apart() {
+    let physics = course("Physics", "GY_01", Weeks::Every, 8, 10);
+    let chemistry = course("Chemistry", "GY_01", Weeks::Every, 10, 12);
+    let mut stats = TimetableStats::default();
+    stats.add(&Timetable::new(0, vec![&physics, &chemistry]));
+    stats.add(&Timetable::new(1, vec![&physics]));
+
+    assert_eq!(stats.course_percentage("Physics", "GY_01"), 100.0);
+    assert_eq!(stats.course_percentage("Chemistry", "GY_01"), 50.0);
+  }
+
+  #[test]
+  fn course_filters_are_counted_before_generation() {
+    let subjects = [Subject {
+      name: "Physics".to_string(),
+      courses: vec![vec![
+        course("Physics", "GY_01", Weeks::Every, 8, 10),
+        course("Physics", "GY_02", Weeks::Every, 10, 12),
+        course("Physics", "GY_03", Weeks::Every, 12, 14),
+      ]],
+    }];
+    let filters = ["min_start_time=09:00", "exclude_course=GY_03"]
+      .map(|spec| filter::parse_filter(spec).unwrap());
+    let filters = filters
+      .iter()
+      .map(|filter| filter.as_ref())
+      .collect::<Vec<_>>();
+
+    assert_eq!(
+      course_filter_funnel(&subjects, &filters),
+      [(1, TimetableCount::Exact(2))]
+    );
+    let subjects = filter::update_courses(&subjects, &filters);
+    let generator = TimetableGenerator::new(&subjects);
+    let timetables = generator::generate_timetables(&generator).unwrap();
+    assert_eq!(filter_funnel(&timetables, &filters), [1, 1]);
+  }
 }
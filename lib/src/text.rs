@@ -0,0 +1,11 @@
+use deunicode::deunicode;
+
+/// Lowercase ASCII form of the text, so `Kovács Ádám` and `kovacs adam` compare equal.
+pub fn normalize(text: &str) -> String {
+  deunicode(text).to_lowercase()
+}
+
+/// Case and accent insensitive `contains`.
+pub fn fuzzy_contains(haystack: &str, needle: &str) -> bool {
+  normalize(haystack).contains(&normalize(needle))
+}
@@ -0,0 +1,243 @@
+use std::{
+  fs,
+  io::{BufRead, BufReader, Read, Write},
+  net::{TcpListener, TcpStream},
+  sync::{Arc, Mutex},
+  thread,
+};
+
+use chrono::{NaiveTime, Weekday};
+use serde_json::Value;
+use timetable_optimizer_lib::{
+  data::CourseType,
+  neptun::{NeptunClient, RegisteredSubject},
+};
+
+const USER_LOGIN: &str = "ABC123";
+const PASSWORD: &str = "secret";
+
+/// A stand-in for the Neptun mobile service, answering with the responses recorded in `tests/data/neptun`.
+struct MockNeptun {
+  base_url: String,
+  /// The called methods and the bodies of the requests.
+  requests: Arc<Mutex<Vec<(String, Value)>>>,
+}
+
+impl MockNeptun {
+  fn start() -> MockNeptun {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!(
+      "http://{}/MobileService.svc",
+      listener.local_addr().unwrap()
+    );
+    let requests = Arc::new(Mutex::new(vec![]));
+
+    let recorded_requests = requests.clone();
+    thread::spawn(move || {
+      for stream in listener.incoming() {
+        respond(stream.unwrap(), &recorded_requests);
+      }
+    });
+
+    MockNeptun { base_url, requests }
+  }
+
+  fn requests(&self) -> Vec<(String, Value)> {
+    self.requests.lock().unwrap().clone()
+  }
+}
+
+fn respond(mut stream: TcpStream, requests: &Mutex<Vec<(String, Value)>>) {
+  let mut reader = BufReader::new(&mut stream);
+  let mut request_line = String::new();
+  reader.read_line(&mut request_line).unwrap();
+  let path = request_line.split_whitespace().nth(1).unwrap().to_string();
+
+  let mut content_length = 0;
+  loop {
+    let mut header = String::new();
+    reader.read_line(&mut header).unwrap();
+    if header.trim().is_empty() {
+      break;
+    }
+    if let Some((name, value)) = header.split_once(':') {
+      if name.eq_ignore_ascii_case("content-length") {
+        content_length = value.trim().parse().unwrap();
+      }
+    }
+  }
+  let mut body = vec![0; content_length];
+  reader.read_exact(&mut body).unwrap();
+  let body: Value = serde_json::from_slice(&body).unwrap();
+
+  let method = path.rsplit('/').next().unwrap().to_string();
+  let (status, response) = match recorded_response(&method, &body) {
+    Some(response) => ("200 OK", response),
+    None => ("404 Not Found", String::new()),
+  };
+  // recorded before responding, so the client can't see the response first
+  requests.lock().unwrap().push((method, body));
+  write!(
+    stream,
+    "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+    response.len()
+  )
+  .unwrap();
+}
+
+fn recorded_response(method: &str, body: &Value) -> Option<String> {
+  let file_name = if body["Password"] != PASSWORD {
+    format!("{method}-wrong-password")
+  } else if let Some(subject_id) = body["SubjectId"].as_u64() {
+    format!("{method}-{subject_id}")
+  } else {
+    method.to_string()
+  };
+  let path = format!(
+    "{}/tests/data/neptun/{file_name}.json",
+    env!("CARGO_MANIFEST_DIR")
+  );
+  fs::read_to_string(path).ok()
+}
+
+#[test]
+fn fetches_the_registered_subjects() {
+  let neptun = MockNeptun::start();
+  let client = NeptunClient::login(&neptun.base_url, USER_LOGIN, PASSWORD).unwrap();
+  let subjects = client.subjects().unwrap();
+
+  let names: Vec<&str> = subjects.iter().map(|s| s.name.as_str()).collect();
+  assert_eq!(names, ["Electronics Basic", "Basic Mathematics"]);
+
+  let electronics = &subjects[0];
+  assert_eq!(electronics.courses.len(), 2);
+  let lecture = &electronics.courses[0][0];
+  assert_eq!(lecture.code, "EB_EA");
+  assert_eq!(lecture.subject_name, "Electronics Basic");
+  assert_eq!(lecture.course_type, CourseType::Lecture);
+  assert_eq!(lecture.occurrence.weekday, Weekday::Wed);
+  assert_eq!(
+    lecture.occurrence.start_time,
+    NaiveTime::from_hms_opt(14, 25, 0).unwrap()
+  );
+  assert_eq!(
+    lecture.occurrence.end_time,
+    NaiveTime::from_hms_opt(16, 5, 0).unwrap()
+  );
+  assert_eq!(lecture.location, "(BA.1.10)");
+  assert_eq!(lecture.teacher, "John Doe");
+  assert_eq!(lecture.enrollment.people_limit, 150);
+
+  let laboratories: Vec<&str> = electronics.courses[1]
+    .iter()
+    .map(|c| c.code.as_str())
+    .collect();
+  assert_eq!(laboratories, ["EB_LA_01", "EB_LA_02"]);
+}
+
+#[test]
+fn sends_the_credentials_and_the_training() {
+  let neptun = MockNeptun::start();
+  let client = NeptunClient::login(&neptun.base_url, USER_LOGIN, PASSWORD).unwrap();
+  client.subjects().unwrap();
+
+  let requests = neptun.requests();
+  let methods: Vec<&str> = requests.iter().map(|(method, _)| method.as_str()).collect();
+  assert_eq!(
+    methods,
+    [
+      "GetTrainings",
+      "GetAddedSubjects",
+      "GetCourses",
+      "GetCourses"
+    ]
+  );
+  for (_, body) in &requests {
+    assert_eq!(body["UserLogin"], USER_LOGIN);
+    assert_eq!(body["Password"], PASSWORD);
+  }
+  assert_eq!(requests[1].1["TrainingId"], 4021);
+  assert_eq!(requests[3].1["SubjectId"], 1002);
+}
+
+#[test]
+fn accepts_a_base_url_ending_with_a_slash() {
+  let neptun = MockNeptun::start();
+  let base_url = format!("{}/", neptun.base_url);
+  let client = NeptunClient::login(&base_url, USER_LOGIN, PASSWORD).unwrap();
+
+  assert_eq!(client.registered_subjects().unwrap().len(), 2);
+}
+
+#[test]
+fn reports_a_wrong_password() {
+  let neptun = MockNeptun::start();
+  let error = NeptunClient::login(&neptun.base_url, USER_LOGIN, "wrong")
+    .err()
+    .unwrap();
+
+  assert_eq!(error, "Neptun: Invalid user name or password");
+}
+
+#[test]
+fn reports_the_invalid_course() {
+  let neptun = MockNeptun::start();
+  let client = NeptunClient::login(&neptun.base_url, USER_LOGIN, PASSWORD).unwrap();
+  let error = client
+    .subject(&RegisteredSubject {
+      subject_id: 1003,
+      subject_code: "PE".to_string(),
+      subject_name: "Physical Education".to_string(),
+    })
+    .unwrap_err();
+
+  assert_eq!(
+    error,
+    "Physical Education, course PE_01: Invalid course type: Sport"
+  );
+}
+
+#[test]
+fn accepts_null_instead_of_an_empty_list() {
+  let neptun = MockNeptun::start();
+  let client = NeptunClient::login(&neptun.base_url, USER_LOGIN, PASSWORD).unwrap();
+  let subject = client
+    .subject(&RegisteredSubject {
+      subject_id: 1004,
+      subject_code: "TH".to_string(),
+      subject_name: "Thesis".to_string(),
+    })
+    .unwrap();
+
+  assert!(subject.courses.is_empty());
+}
+
+#[test]
+fn accepts_null_course_details() {
+  let neptun = MockNeptun::start();
+  let client = NeptunClient::login(&neptun.base_url, USER_LOGIN, PASSWORD).unwrap();
+  let subject = client
+    .subject(&RegisteredSubject {
+      subject_id: 1005,
+      subject_code: "HI".to_string(),
+      subject_name: "History".to_string(),
+    })
+    .unwrap();
+
+  let course = &subject.courses[0][0];
+  assert_eq!(course.code, "HI_01");
+  assert_eq!(course.teacher, "");
+  assert_eq!(course.enrollment.people_limit, 0);
+}
+
+#[test]
+fn reports_an_unreachable_server() {
+  let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+  let base_url = format!("http://{}", listener.local_addr().unwrap());
+  drop(listener);
+  let error = NeptunClient::login(&base_url, USER_LOGIN, PASSWORD)
+    .err()
+    .unwrap();
+
+  assert!(error.starts_with(&format!("Request failed: {base_url}/GetTrainings")));
+}
@@ -0,0 +1,6 @@
+use gloo::worker::Registrable;
+use timetable_optimizer_web::generator_worker::GeneratorWorker;
+
+fn main() {
+  GeneratorWorker::registrar().register();
+}
@@ -7,6 +7,9 @@ pub struct CourseProps {
   pub course: Course,
   pub on_delete: Callback<String>,
   pub on_toggle_visibility: Callback<String>,
+  pub on_restore: Callback<String>,
+  pub on_edit: Callback<String>,
+  pub on_duplicate: Callback<String>,
 }
 
 #[function_component(CourseComponent)]
@@ -25,13 +28,37 @@ pub fn course_component(props: &CourseProps) -> Html {
     move |_| on_toggle_visibility.emit(code.clone())
   };
 
+  let code = course.code.clone();
+  let on_restore = {
+    let on_restore = props.on_restore.clone();
+    move |_| on_restore.emit(code.clone())
+  };
+
+  let code = course.code.clone();
+  let on_edit = {
+    let on_edit = props.on_edit.clone();
+    move |_| on_edit.emit(code.clone())
+  };
+
+  let code = course.code.clone();
+  let on_duplicate = {
+    let on_duplicate = props.on_duplicate.clone();
+    move |_| on_duplicate.emit(code.clone())
+  };
+
   html! {
     <tr class={ classes!(course.is_hidden_by_user.as_some("opacity-50")) }>
       <td>
-        <button onclick={on_delete}>{ "Delete" }</button>
-        <button onclick={on_togle_visiblity}>{
-          if course.is_hidden_by_user { "Show" } else { "Hide" }
-        }</button>
+        if course.is_deleted {
+          <button onclick={on_restore}>{ "Restore" }</button>
+        } else {
+          <button onclick={on_delete}>{ "Delete" }</button>
+          <button onclick={on_togle_visiblity}>{
+            if course.is_hidden_by_user { "Show" } else { "Hide" }
+          }</button>
+          <button onclick={on_edit}>{ "Edit" }</button>
+          <button onclick={on_duplicate}>{ "Duplicate" }</button>
+        }
       </td>
       <td>{ &course.code }</td>
       <td>{ &course.course_type.to_string() }</td>
@@ -0,0 +1,190 @@
+use chrono::{NaiveTime, Weekday};
+use timetable_optimizer_lib::data::{Course, CourseType, Enrollment, Occurrence, Weeks};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+const COURSE_TYPES: &[CourseType] = &[
+  CourseType::Lecture,
+  CourseType::Laboratory,
+  CourseType::Practice,
+  CourseType::ELearning,
+];
+const WEEKDAYS: &[Weekday] = &[
+  Weekday::Mon,
+  Weekday::Tue,
+  Weekday::Wed,
+  Weekday::Thu,
+  Weekday::Fri,
+  Weekday::Sat,
+  Weekday::Sun,
+];
+const WEEKS: &[Option<Weeks>] = &[
+  None,
+  Some(Weeks::Every),
+  Some(Weeks::Odd),
+  Some(Weeks::Even),
+];
+
+/// An empty course of the subject, the starting point of a course added by hand.
+pub fn new_course(subject_name: &str) -> Course {
+  let mut course = Course::new(
+    subject_name.to_string(),
+    String::new(),
+    CourseType::Lecture,
+    Enrollment::default(),
+    String::new(),
+    String::new(),
+    String::new(),
+    String::new(),
+    String::new(),
+    String::new(),
+    Occurrence {
+      weeks: None,
+      weekday: Weekday::Mon,
+      start_time: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+      end_time: NaiveTime::from_hms_opt(9, 30, 0).unwrap(),
+    },
+  );
+  course.is_added_by_user = true;
+  course.update_hash();
+  course
+}
+
+#[derive(Properties, PartialEq)]
+pub struct CourseEditorProps {
+  pub course: Course,
+  /// Codes of all the other courses, course codes have to be unique.
+  pub taken_codes: Vec<String>,
+  pub on_save: Callback<Course>,
+  pub on_cancel: Callback<()>,
+}
+
+#[function_component(CourseEditorComponent)]
+pub fn course_editor_component(props: &CourseEditorProps) -> Html {
+  let draft = use_state(|| props.course.clone());
+  let error = use_state(|| None::<String>);
+
+  {
+    // another course was opened in the editor
+    let draft = draft.clone();
+    let error = error.clone();
+    use_effect_with(props.course.clone(), move |course| {
+      draft.set(course.clone());
+      error.set(None);
+    });
+  }
+
+  let update = |update_fn: fn(&mut Course, String)| {
+    let draft = draft.clone();
+    move |value: String| {
+      let mut new_draft = (*draft).clone();
+      update_fn(&mut new_draft, value);
+      draft.set(new_draft);
+    }
+  };
+  let text_input = |value: &str, update_fn: fn(&mut Course, String)| {
+    let update = update(update_fn);
+    let oninput = move |e: InputEvent| {
+      let input: HtmlInputElement = e.target_unchecked_into();
+      update(input.value());
+    };
+    html! { <input type="text" value={value.to_string()} {oninput} /> }
+  };
+  let time_input = |value: NaiveTime, update_fn: fn(&mut Course, String)| {
+    let update = update(update_fn);
+    let oninput = move |e: InputEvent| {
+      let input: HtmlInputElement = e.target_unchecked_into();
+      update(input.value());
+    };
+    html! { <input type="time" value={value.format("%H:%M").to_string()} {oninput} /> }
+  };
+  let select_input = |options: Vec<String>, selected: usize, update_fn: fn(&mut Course, String)| {
+    let update = update(update_fn);
+    let onchange = move |e: Event| {
+      let select: HtmlSelectElement = e.target_unchecked_into();
+      update(select.selected_index().to_string());
+    };
+    html! {
+      <select {onchange}>
+        { for options.into_iter().enumerate().map(|(i, option)| html! {
+          <option selected={i == selected}>{ option }</option>
+        }) }
+      </select>
+    }
+  };
+
+  let on_save = {
+    let draft = draft.clone();
+    let error = error.clone();
+    let taken_codes = props.taken_codes.clone();
+    let on_save = props.on_save.clone();
+    move |_| {
+      let mut course = (*draft).clone();
+      course.code = course.code.trim().to_string();
+      if course.code.is_empty() {
+        error.set(Some("The course code is required".to_string()));
+      } else if taken_codes.contains(&course.code) {
+        error.set(Some(format!(
+          "The course code {} is already taken",
+          course.code
+        )));
+      } else if course.occurrence.end_time <= course.occurrence.start_time {
+        error.set(Some("The course has to end after it starts".to_string()));
+      } else {
+        course.update_hash();
+        on_save.emit(course);
+      }
+    }
+  };
+  let on_cancel = {
+    let on_cancel = props.on_cancel.clone();
+    move |_| on_cancel.emit(())
+  };
+
+  let course = &*draft;
+  let occ = &course.occurrence;
+  html! {
+    <div class="flex flex-wrap gap-2 my-2">
+      <label>{ "Code" }{ text_input(&course.code, |course, value| course.code = value) }</label>
+      <label>{ "Type" }{ select_input(
+        COURSE_TYPES.iter().map(CourseType::to_string).collect(),
+        COURSE_TYPES.iter().position(|&course_type| course_type == course.course_type).unwrap(),
+        |course, value| course.course_type = COURSE_TYPES[value.parse::<usize>().unwrap()],
+      ) }</label>
+      <label>{ "Weekday" }{ select_input(
+        WEEKDAYS.iter().map(Weekday::to_string).collect(),
+        occ.weekday.num_days_from_monday() as usize,
+        |course, value| course.occurrence.weekday = WEEKDAYS[value.parse::<usize>().unwrap()],
+      ) }</label>
+      <label>{ "Start" }{ time_input(occ.start_time, |course, value| {
+        if let Ok(time) = NaiveTime::parse_from_str(&value, "%H:%M") {
+          course.occurrence.start_time = time;
+        }
+      }) }</label>
+      <label>{ "End" }{ time_input(occ.end_time, |course, value| {
+        if let Ok(time) = NaiveTime::parse_from_str(&value, "%H:%M") {
+          course.occurrence.end_time = time;
+        }
+      }) }</label>
+      <label>{ "Weeks" }{ select_input(
+        WEEKS.iter().map(weeks_name).collect(),
+        WEEKS.iter().position(|weeks| *weeks == occ.weeks).unwrap(),
+        |course, value| course.occurrence.weeks = WEEKS[value.parse::<usize>().unwrap()].clone(),
+      ) }</label>
+      <label>{ "Location" }{ text_input(&course.location, |course, value| course.location = value) }</label>
+      <label>{ "Teacher" }{ text_input(&course.teacher, |course, value| course.teacher = value) }</label>
+      <button onclick={on_save}>{ "Save" }</button>
+      <button onclick={on_cancel}>{ "Cancel" }</button>
+      if let Some(error) = &*error {
+        <p>{ error }</p>
+      }
+    </div>
+  }
+}
+
+fn weeks_name(weeks: &Option<Weeks>) -> String {
+  match weeks {
+    None => "Unknown".to_string(),
+    Some(weeks) => format!("{weeks:?}"),
+  }
+}
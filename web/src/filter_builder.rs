@@ -0,0 +1,544 @@
+use itertools::Itertools;
+use timetable_optimizer_lib::{
+  data::{CourseType, Subject},
+  filter,
+};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+const WEEKDAYS: &[&str] = &["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+const COURSE_TYPES: &[CourseType] = &[
+  CourseType::Lecture,
+  CourseType::Laboratory,
+  CourseType::Practice,
+  CourseType::ELearning,
+];
+
+#[derive(PartialEq, Clone, Copy)]
+enum Field {
+  Time,
+  Weekday,
+  Number,
+  Teacher,
+  CourseCode,
+  CourseType,
+  Building,
+  Text,
+  Regex,
+  /// Alternatives of weekdays that all have to be free: `mon+fri|sat`
+  WeekdayAlternatives,
+  /// A checkbox adding the `,ignore_optional` suffix.
+  IgnoreOptional,
+  /// The break minutes of `max_consecutive_minutes`, the default is used when empty.
+  MinBreak,
+  /// Rows of weekday and time range: `mon,08:00-16:00;tue,10:00-18:00`
+  Windows,
+  /// Rows of two buildings and the minutes between them: `BA,BC,10;BC,TG,15`
+  TravelTimes,
+}
+
+impl Field {
+  /// The cells of a field with a list of `;` separated rows, and the separators between the cells.
+  fn row_layout(self) -> Option<([Field; 3], [char; 2])> {
+    match self {
+      Field::Windows => Some(([Field::Weekday, Field::Time, Field::Time], [',', '-'])),
+      Field::TravelTimes => Some((
+        [Field::Building, Field::Building, Field::Number],
+        [',', ','],
+      )),
+      _ => None,
+    }
+  }
+
+  /// The part of the filter specification the value of the field becomes.
+  fn spec_value(self, value: &str) -> String {
+    match self {
+      Field::WeekdayAlternatives => value
+        .split('|')
+        .filter(|weekdays| !weekdays.is_empty())
+        .join("|"),
+      Field::IgnoreOptional if !value.is_empty() => ",ignore_optional".to_string(),
+      Field::IgnoreOptional => String::new(),
+      Field::MinBreak if !value.is_empty() => format!(",{value}"),
+      _ => value.to_string(),
+    }
+  }
+}
+
+struct FilterKind {
+  key: &'static str,
+  /// The value of the filter specification, `{0}`, `{1}`... are replaced by the fields.
+  template: &'static str,
+  fields: &'static [Field],
+  /// Whether the filter can be limited to a single subject with a `subject:` prefix,
+  /// or a `subject=name;` prefix for regexes.
+  per_subject: bool,
+}
+
+const FILTER_KINDS: &[FilterKind] = &[
+  kind("min_start_time", "{0}", &[Field::Time], false),
+  kind("max_end_time", "{0}", &[Field::Time], false),
+  kind(
+    "start_after",
+    "{0},{1}",
+    &[Field::Weekday, Field::Time],
+    false,
+  ),
+  kind(
+    "end_before",
+    "{0},{1}",
+    &[Field::Weekday, Field::Time],
+    false,
+  ),
+  kind(
+    "no_course_between",
+    "{0},{1},{2}",
+    &[Field::Weekday, Field::Time, Field::Time],
+    false,
+  ),
+  kind("availability", "{0}", &[Field::Windows], false),
+  kind(
+    "free_workdays",
+    "{0}{1}",
+    &[Field::Number, Field::IgnoreOptional],
+    false,
+  ),
+  kind(
+    "free_weekdays",
+    "{0}{1}",
+    &[Field::WeekdayAlternatives, Field::IgnoreOptional],
+    false,
+  ),
+  kind(
+    "max_gap_minutes_between_courses",
+    "{0}",
+    &[Field::Number],
+    false,
+  ),
+  kind("max_hours_per_day", "{0}", &[Field::Number], false),
+  kind("max_courses_per_day", "{0}", &[Field::Number], false),
+  kind(
+    "max_consecutive_minutes",
+    "{0}{1}",
+    &[Field::Number, Field::MinBreak],
+    false,
+  ),
+  kind(
+    "lunch_break",
+    "{0}-{1},{2}",
+    &[Field::Time, Field::Time, Field::Number],
+    false,
+  ),
+  kind("travel_times", "csv:{0}", &[Field::TravelTimes], false),
+  kind("exclude_teacher", "{0}", &[Field::Teacher], true),
+  kind("include_teacher", "{0}", &[Field::Teacher], true),
+  kind("require_course", "{0}", &[Field::CourseCode], false),
+  kind("exclude_course", "{0}", &[Field::CourseCode], false),
+  kind("optional_course_type", "{0}", &[Field::CourseType], true),
+  kind("language", "{0}", &[Field::Text], true),
+  kind("site", "{0}", &[Field::Text], true),
+  kind("exclude_site", "{0}", &[Field::Text], true),
+  kind("include_comment", "{0}", &[Field::Regex], true),
+  kind("exclude_comment", "{0}", &[Field::Regex], true),
+];
+
+const fn kind(
+  key: &'static str,
+  template: &'static str,
+  fields: &'static [Field],
+  per_subject: bool,
+) -> FilterKind {
+  FilterKind {
+    key,
+    template,
+    fields,
+    per_subject,
+  }
+}
+
+impl FilterKind {
+  fn spec(&self, subject: &str, values: &[String]) -> String {
+    let value =
+      self
+        .fields
+        .iter()
+        .enumerate()
+        .fold(self.template.to_string(), |template, (i, field)| {
+          let value = values.get(i).map_or("", String::as_str);
+          template.replace(&format!("{{{i}}}"), &field.spec_value(value))
+        });
+    if self.per_subject && !subject.is_empty() && self.fields.contains(&Field::Regex) {
+      format!("{}=subject={subject};{value}", self.key)
+    } else if self.per_subject && !subject.is_empty() {
+      format!("{}={subject}:{value}", self.key)
+    } else {
+      format!("{}={value}", self.key)
+    }
+  }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct FilterBuilderProps {
+  pub subjects: Vec<Subject>,
+  pub filters: Vec<String>,
+  pub on_change: Callback<Vec<String>>,
+}
+
+#[function_component(FilterBuilderComponent)]
+pub fn filter_builder_component(props: &FilterBuilderProps) -> Html {
+  let kind_index = use_state(|| 0);
+  let values = use_state(Vec::<String>::new);
+  let subject = use_state(String::new);
+  let error = use_state(|| None::<String>);
+
+  let kind = &FILTER_KINDS[*kind_index];
+
+  let on_kind_change = {
+    let kind_index = kind_index.clone();
+    let values = values.clone();
+    let error = error.clone();
+    move |e: Event| {
+      let select: HtmlSelectElement = e.target_unchecked_into();
+      kind_index.set(select.selected_index().max(0) as usize);
+      values.set(vec![]);
+      error.set(None);
+    }
+  };
+
+  let on_subject_change = {
+    let subject = subject.clone();
+    move |e: Event| {
+      let select: HtmlSelectElement = e.target_unchecked_into();
+      subject.set(select.value());
+    }
+  };
+
+  let on_add = {
+    let values = values.clone();
+    let subject = subject.clone();
+    let error = error.clone();
+    let filters = props.filters.clone();
+    let on_change = props.on_change.clone();
+    move |_| {
+      let spec = kind.spec(&subject, &values);
+      match filter::parse_filter(&spec) {
+        Ok(_) => {
+          let mut new_filters = filters.clone();
+          new_filters.push(spec);
+          on_change.emit(new_filters);
+          error.set(None);
+        }
+        Err(e) => error.set(Some(e)),
+      }
+    }
+  };
+
+  let on_remove = |index: usize| {
+    let filters = props.filters.clone();
+    let on_change = props.on_change.clone();
+    move |_| {
+      let mut new_filters = filters.clone();
+      new_filters.remove(index);
+      on_change.emit(new_filters);
+    }
+  };
+
+  let field_input = |(i, field): (usize, &Field)| {
+    let value = values.get(i).cloned().unwrap_or_default();
+    let on_value_change = {
+      let values = values.clone();
+      Callback::from(move |value: String| {
+        let mut new_values = (*values).clone();
+        new_values.resize(new_values.len().max(i + 1), String::new());
+        new_values[i] = value;
+        values.set(new_values);
+      })
+    };
+
+    match field.row_layout() {
+      Some(layout) => rows_input(layout, &value, on_value_change, &props.subjects),
+      None => match field {
+        Field::WeekdayAlternatives => weekday_alternatives_input(&value, on_value_change),
+        _ => value_input(*field, &value, on_value_change, &props.subjects),
+      },
+    }
+  };
+
+  html! {
+    <div>
+      <h1>{ "Filters" }</h1>
+      <ul>
+        { for props.filters.iter().enumerate().map(|(i, spec)| html! {
+          <li>
+            <button onclick={on_remove(i)}>{ "Remove" }</button>
+            { spec }
+          </li>
+        }) }
+      </ul>
+      <div class="flex gap-2">
+        <select onchange={on_kind_change}>
+          { for FILTER_KINDS.iter().enumerate().map(|(i, kind)| html! {
+            <option selected={i == *kind_index}>{ kind.key }</option>
+          }) }
+        </select>
+        if kind.per_subject {
+          <select onchange={on_subject_change}>
+            <option value="" selected={subject.is_empty()}>{ "All subjects" }</option>
+            { for props.subjects.iter().map(|s| html! {
+              <option selected={s.name == *subject}>{ &s.name }</option>
+            }) }
+          </select>
+        }
+        { for kind.fields.iter().enumerate().map(field_input) }
+        <button onclick={on_add}>{ "Add" }</button>
+      </div>
+      if let Some(error) = &*error {
+        <p>{ error }</p>
+      }
+    </div>
+  }
+}
+
+/// The input of a single value, the rows of the list fields also consist of these.
+fn value_input(
+  field: Field,
+  value: &str,
+  on_change: Callback<String>,
+  subjects: &[Subject],
+) -> Html {
+  let on_input = {
+    let on_change = on_change.clone();
+    move |e: InputEvent| {
+      let input: HtmlInputElement = e.target_unchecked_into();
+      on_change.emit(input.value());
+    }
+  };
+  let on_select = {
+    let on_change = on_change.clone();
+    move |e: Event| {
+      let select: HtmlSelectElement = e.target_unchecked_into();
+      on_change.emit(select.value());
+    }
+  };
+
+  match field {
+    Field::Time => html! { <input type="time" value={value.to_string()} oninput={on_input} /> },
+    Field::Number => {
+      html! { <input type="number" min="0" step="any" value={value.to_string()} oninput={on_input} /> }
+    }
+    Field::MinBreak => html! {
+      <input
+        type="number"
+        min="0"
+        placeholder="Break minutes (15)"
+        value={value.to_string()}
+        oninput={on_input}
+      />
+    },
+    Field::Text | Field::Regex => {
+      html! { <input type="text" value={value.to_string()} oninput={on_input} /> }
+    }
+    Field::IgnoreOptional => {
+      let onchange = move |e: Event| {
+        let input: HtmlInputElement = e.target_unchecked_into();
+        on_change.emit(if input.checked() { "true" } else { "" }.to_string());
+      };
+      html! {
+        <label>
+          <input type="checkbox" checked={!value.is_empty()} {onchange} />
+          { "Ignore optional courses" }
+        </label>
+      }
+    }
+    Field::Weekday => select_input(
+      WEEKDAYS.iter().map(|weekday| weekday.to_string()),
+      value,
+      on_select,
+    ),
+    Field::CourseType => select_input(
+      COURSE_TYPES.iter().map(CourseType::to_string),
+      value,
+      on_select,
+    ),
+    Field::Teacher => select_input(teachers(subjects), value, on_select),
+    Field::CourseCode => select_input(course_codes(subjects), value, on_select),
+    Field::Building => select_input(buildings(subjects), value, on_select),
+    Field::WeekdayAlternatives | Field::Windows | Field::TravelTimes => {
+      unreachable!("list fields are rendered by their own inputs")
+    }
+  }
+}
+
+/// Checkboxes of the weekdays for each alternative, the checked weekdays are joined with `+`,
+/// the alternatives with `|`.
+fn weekday_alternatives_input(value: &str, on_change: Callback<String>) -> Html {
+  let alternatives = value.split('|').map(str::to_string).collect_vec();
+  let on_toggle = |alternative: usize, weekday: &'static str| {
+    let alternatives = alternatives.clone();
+    let on_change = on_change.clone();
+    move |_| {
+      let mut alternatives = alternatives.clone();
+      let weekdays = alternatives[alternative]
+        .split('+')
+        .filter(|&other| !other.is_empty() && other != weekday)
+        .collect_vec();
+      let is_checked = alternatives[alternative]
+        .split('+')
+        .any(|other| other == weekday);
+      let toggled = match is_checked {
+        true => weekdays,
+        false => WEEKDAYS
+          .iter()
+          .copied()
+          .filter(|&other| other == weekday || weekdays.contains(&other))
+          .collect(),
+      };
+      alternatives[alternative] = toggled.join("+");
+      on_change.emit(alternatives.join("|"));
+    }
+  };
+  let on_add = {
+    let value = value.to_string();
+    let on_change = on_change.clone();
+    move |_| on_change.emit(format!("{value}|"))
+  };
+
+  html! {
+    <div class="flex flex-col">
+      { for alternatives.iter().enumerate().map(|(i, weekdays)| html! {
+        <div class="flex gap-1">
+          if i > 0 {
+            <span>{ "or" }</span>
+          }
+          { for WEEKDAYS.iter().map(|&weekday| html! {
+            <label>
+              <input
+                type="checkbox"
+                checked={weekdays.split('+').any(|other| other == weekday)}
+                onchange={on_toggle(i, weekday)}
+              />
+              { weekday }
+            </label>
+          }) }
+        </div>
+      }) }
+      <button onclick={on_add}>{ "Add alternative" }</button>
+    </div>
+  }
+}
+
+/// A list of rows with three inputs each, the value is the `;` separated rows.
+fn rows_input(
+  (cells, separators): ([Field; 3], [char; 2]),
+  value: &str,
+  on_change: Callback<String>,
+  subjects: &[Subject],
+) -> Html {
+  let rows = value
+    .split(';')
+    .filter(|_| !value.is_empty())
+    .map(|row| {
+      let (first, rest) = row.split_once(separators[0]).unwrap_or((row, ""));
+      let (second, third) = rest.split_once(separators[1]).unwrap_or((rest, ""));
+      [first, second, third].map(str::to_string)
+    })
+    .collect_vec();
+  let join = move |rows: &[[String; 3]]| {
+    rows
+      .iter()
+      .map(|[first, second, third]| {
+        format!("{first}{}{second}{}{third}", separators[0], separators[1])
+      })
+      .join(";")
+  };
+
+  let on_add = {
+    let rows = rows.clone();
+    let on_change = on_change.clone();
+    move |_| {
+      let mut rows = rows.clone();
+      rows.push(Default::default());
+      on_change.emit(join(&rows));
+    }
+  };
+  let on_remove = |row: usize| {
+    let rows = rows.clone();
+    let on_change = on_change.clone();
+    move |_| {
+      let mut rows = rows.clone();
+      rows.remove(row);
+      on_change.emit(join(&rows));
+    }
+  };
+  let on_cell_change = |row: usize, cell: usize| {
+    let rows = rows.clone();
+    let on_change = on_change.clone();
+    Callback::from(move |value: String| {
+      let mut rows = rows.clone();
+      rows[row][cell] = value;
+      on_change.emit(join(&rows));
+    })
+  };
+
+  html! {
+    <div class="flex flex-col">
+      { for rows.iter().enumerate().map(|(i, row)| html! {
+        <div class="flex gap-1">
+          { for cells.iter().zip(row).enumerate().map(|(cell, (&field, value))| {
+            value_input(field, value, on_cell_change(i, cell), subjects)
+          }) }
+          <button onclick={on_remove(i)}>{ "Remove" }</button>
+        </div>
+      }) }
+      <button onclick={on_add}>{ "Add row" }</button>
+    </div>
+  }
+}
+
+/// A select with an empty option, so the first option is also emitted when chosen.
+fn select_input(
+  options: impl Iterator<Item = String>,
+  value: &str,
+  on_change: impl Fn(Event) + 'static,
+) -> Html {
+  html! {
+    <select onchange={on_change}>
+      <option value="" selected={value.is_empty()}></option>
+      { for options.map(|option| html! {
+        <option selected={option == value}>{ option }</option>
+      }) }
+    </select>
+  }
+}
+
+fn teachers(subjects: &[Subject]) -> impl Iterator<Item = String> + '_ {
+  subjects
+    .iter()
+    .flat_map(|subject| &subject.courses)
+    .flatten()
+    .flat_map(|course| course.teachers())
+    .map(str::to_string)
+    .sorted()
+    .dedup()
+}
+
+fn course_codes(subjects: &[Subject]) -> impl Iterator<Item = String> + '_ {
+  subjects
+    .iter()
+    .flat_map(|subject| &subject.courses)
+    .flatten()
+    .map(|course| course.code.clone())
+    .sorted()
+    .dedup()
+}
+
+fn buildings(subjects: &[Subject]) -> impl Iterator<Item = String> + '_ {
+  subjects
+    .iter()
+    .flat_map(|subject| &subject.courses)
+    .flatten()
+    .map(|course| course.building().to_string())
+    .filter(|building| !building.is_empty())
+    .sorted()
+    .dedup()
+}
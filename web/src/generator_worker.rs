@@ -0,0 +1,207 @@
+use gloo::{
+  timers::callback::Timeout,
+  worker::{HandlerId, Worker, WorkerScope},
+};
+use serde::{Deserialize, Serialize};
+use timetable_optimizer_lib::{
+  bottleneck::{self, Conflicts, Unlock},
+  data::Subject,
+  filter::{self, Filter},
+  generator::{TimetableCount, TimetableGenerator},
+  stats::{self, TimetableStats},
+};
+
+/// Combinations checked at once, new inputs are only received between the chunks.
+const CHUNK_SIZE: u64 = 10_000;
+
+/// The bottlenecks are only searched below this many timetables, as the search is slow.
+const FEW_TIMETABLES: u64 = 10;
+
+#[derive(Serialize, Deserialize)]
+pub struct GeneratorInput {
+  /// Outputs of previous jobs can still arrive after a new input was sent, this tells them apart.
+  pub job: u32,
+  pub subjects: Vec<Subject>,
+  pub filters: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum GeneratorOutput {
+  Progress {
+    job: u32,
+    checked: u64,
+    total: u64,
+    /// Timetables without clashes in the last chunk.
+    valid: u64,
+    /// Timetables of the last chunk left after each filter.
+    funnel: Vec<u64>,
+    /// Combination indices of the timetables found in the last chunk.
+    timetables: Vec<u64>,
+    /// Statistics of all the timetables found so far.
+    stats: Box<TimetableStats>,
+  },
+  /// Count of the timetables without clashes, sent before the first progress.
+  Count {
+    job: u32,
+    count: TimetableCount,
+    /// The count after each filter updating the courses, with the index of the filter.
+    course_funnel: Vec<(usize, TimetableCount)>,
+  },
+  /// Sent after the count, only when there are few timetables.
+  Bottlenecks {
+    job: u32,
+    conflicts: Conflicts,
+    unlocks: Vec<Unlock>,
+  },
+  Error {
+    job: u32,
+    message: String,
+  },
+}
+
+pub struct ContinueJob(u32);
+
+pub struct GeneratorWorker {
+  job: Option<Job>,
+}
+
+struct Job {
+  id: u32,
+  handler: HandlerId,
+  /// Built once per job, the chunks continue where the previous one ended.
+  generator: TimetableGenerator,
+  total: u64,
+  filters: Vec<Box<dyn Filter>>,
+  next_index: u64,
+  /// Collected chunk by chunk, so the found timetables don't have to be generated again.
+  stats: TimetableStats,
+}
+
+impl Worker for GeneratorWorker {
+  type Message = ContinueJob;
+  type Input = GeneratorInput;
+  type Output = GeneratorOutput;
+
+  fn create(_scope: &WorkerScope<Self>) -> Self {
+    GeneratorWorker { job: None }
+  }
+
+  fn update(&mut self, scope: &WorkerScope<Self>, ContinueJob(id): Self::Message) {
+    let Some(job) = self.job.as_mut().filter(|job| job.id == id) else {
+      // the job was replaced by a newer one
+      return;
+    };
+
+    let generator = &job.generator;
+    let total = job.total;
+    let end = (job.next_index + CHUNK_SIZE).min(total);
+    let filters = job
+      .filters
+      .iter()
+      .map(|filter| filter.as_ref())
+      .collect::<Vec<_>>();
+    let mut valid = 0;
+    let mut funnel = vec![0; filters.len()];
+    let mut timetables = vec![];
+    for index in job.next_index..end {
+      let Some(timetable) = generator.timetable_at(index) else {
+        continue;
+      };
+      valid += 1;
+      let passed = stats::passed_filter_count(&timetable, &filters);
+      for count in &mut funnel[..passed] {
+        *count += 1;
+      }
+      if passed == filters.len() {
+        job.stats.add(&timetable);
+        timetables.push(index);
+      }
+    }
+    job.next_index = end;
+
+    scope.respond(
+      job.handler,
+      GeneratorOutput::Progress {
+        job: id,
+        checked: end,
+        total,
+        valid,
+        funnel,
+        timetables,
+        stats: Box::new(job.stats.clone()),
+      },
+    );
+
+    if end < total {
+      // a timeout instead of sending the message directly lets new inputs arrive in between
+      let continue_job = scope.callback(ContinueJob);
+      Timeout::new(0, move || continue_job(id)).forget();
+    } else {
+      self.job = None;
+    }
+  }
+
+  fn received(&mut self, scope: &WorkerScope<Self>, input: Self::Input, handler: HandlerId) {
+    let filters = input
+      .filters
+      .iter()
+      .map(|spec| filter::parse_filter(spec))
+      .collect::<Result<Vec<_>, _>>();
+    let filters = match filters {
+      Ok(filters) => filters,
+      Err(message) => {
+        let output = GeneratorOutput::Error {
+          job: input.job,
+          message,
+        };
+        scope.respond(handler, output);
+        self.job = None;
+        return;
+      }
+    };
+
+    let filter_refs = filters
+      .iter()
+      .map(|filter| filter.as_ref())
+      .collect::<Vec<_>>();
+    let subjects = filter::update_courses(&input.subjects, &filter_refs);
+    let generator = TimetableGenerator::new(&subjects);
+    let Some(total) = generator.combination_count() else {
+      let output = GeneratorOutput::Error {
+        job: input.job,
+        message: "Too many combinations to check, hide some courses".to_string(),
+      };
+      scope.respond(handler, output);
+      self.job = None;
+      return;
+    };
+    // counting is limited, so it doesn't hold up the generation for long
+    let count = generator.timetable_count();
+    scope.respond(
+      handler,
+      GeneratorOutput::Count {
+        job: input.job,
+        count,
+        course_funnel: stats::course_filter_funnel(&input.subjects, &filter_refs),
+      },
+    );
+    if matches!(count, TimetableCount::Exact(count) if count <= FEW_TIMETABLES) {
+      let output = GeneratorOutput::Bottlenecks {
+        job: input.job,
+        conflicts: bottleneck::conflicting_groups(&subjects),
+        unlocks: bottleneck::unlocking_courses(&subjects),
+      };
+      scope.respond(handler, output);
+    }
+    self.job = Some(Job {
+      id: input.job,
+      handler,
+      generator,
+      total,
+      filters,
+      next_index: 0,
+      stats: TimetableStats::default(),
+    });
+    scope.send_message(ContinueJob(input.job));
+  }
+}
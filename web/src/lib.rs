@@ -1,66 +1,364 @@
 mod course;
+mod course_editor;
+mod filter_builder;
+pub mod generator_worker;
+mod scenarios;
 mod statistics;
 mod storage;
 mod subject;
+mod timetable;
 mod timetable_generator;
 mod upload;
 
-use timetable_optimizer_lib::data::UpdateSubjectsByCourseCode;
+use timetable_optimizer_lib::data::{Course, MergeReport, Subject, UpdateSubjectsByCourseCode};
+use timetable_optimizer_lib::share::{self, SharedTimetable};
+use web_sys::wasm_bindgen::JsValue;
 use yew::prelude::*;
 
-use crate::statistics::StatisticsComponent;
-use crate::subject::SubjectsComponent;
-use crate::upload::UploadComponent;
+use crate::filter_builder::FilterBuilderComponent;
+use crate::scenarios::ScenariosComponent;
+use crate::storage::Scenario;
+use crate::subject::{SubjectCallbacks, SubjectsComponent};
 use crate::timetable_generator::TimetableGenerator;
+use crate::upload::UploadComponent;
+
+/// Edits of the subjects that can be undone.
+const HISTORY_LIMIT: usize = 20;
 
 #[function_component(AppComponent)]
 pub fn app_component() -> Html {
+  // a link to a shared timetable opens in a new scenario, before the current one is loaded
+  let shared = use_memo((), |_| open_shared_timetable());
   let subjects = use_state(storage::load_subjects);
+  let filters = use_state(storage::load_filters);
+  // previous states of the subjects, the last one is restored by undo
+  let history = use_state(Vec::<Vec<Subject>>::new);
+  let upload_reports = use_state(Vec::<String>::new);
+  let scenario_names = use_state(scenario_names);
+  let current_scenario = use_state(storage::load_current_scenario_name);
 
-  let update_subjects = {
+  let on_scenarios_change = {
     let subjects = subjects.clone();
-    move |new_subjects| {
+    let filters = filters.clone();
+    let history = history.clone();
+    let upload_reports = upload_reports.clone();
+    let scenario_names = scenario_names.clone();
+    let current_scenario = current_scenario.clone();
+    move |_| {
+      let scenario = storage::load_current_scenario();
+      subjects.set(scenario.subjects);
+      filters.set(scenario.filters);
+      // the history belongs to the previous scenario
+      history.set(vec![]);
+      upload_reports.set(vec![]);
+      scenario_names.set(self::scenario_names());
+      current_scenario.set(scenario.name);
+    }
+  };
+
+  let set_subjects = {
+    let subjects = subjects.clone();
+    move |new_subjects: Vec<Subject>| {
       storage::save_subjects(&new_subjects);
       subjects.set(new_subjects);
     }
   };
 
-  let on_delete = {
+  let update_subjects = {
+    let subjects = subjects.clone();
+    let history = history.clone();
+    let set_subjects = set_subjects.clone();
+    move |new_subjects: Vec<Subject>| {
+      let mut new_history = (*history).clone();
+      new_history.push((*subjects).clone());
+      if new_history.len() > HISTORY_LIMIT {
+        new_history.remove(0);
+      }
+      history.set(new_history);
+      set_subjects(new_subjects);
+    }
+  };
+
+  let on_undo = {
+    let history = history.clone();
+    move |_| {
+      let mut new_history = (*history).clone();
+      if let Some(previous_subjects) = new_history.pop() {
+        history.set(new_history);
+        set_subjects(previous_subjects);
+      }
+    }
+  };
+
+  let update_course = |update_fn: fn(&mut Course)| {
+    let subjects = subjects.clone();
+    let update_subjects = update_subjects.clone();
+    Callback::from(move |course_code: String| {
+      let mut new_subjects = (*subjects).clone();
+      new_subjects.update_subjects_by_course_code(course_code, Box::new(update_fn));
+      update_subjects(new_subjects);
+    })
+  };
+
+  let on_set_group_hidden = {
+    let subjects = subjects.clone();
+    let update_subjects = update_subjects.clone();
+    move |(subject_name, group, hidden): (String, usize, bool)| {
+      let mut new_subjects = (*subjects).clone();
+      let subject = new_subjects
+        .iter_mut()
+        .find(|subject| subject.name == subject_name)
+        .unwrap();
+      for course in &mut subject.courses[group] {
+        course.is_hidden_by_user = hidden;
+      }
+      update_subjects(new_subjects);
+    }
+  };
+  let on_set_subject_hidden = {
+    let subjects = subjects.clone();
+    let update_subjects = update_subjects.clone();
+    move |(subject_name, hidden): (String, bool)| {
+      let mut new_subjects = (*subjects).clone();
+      let subject = new_subjects
+        .iter_mut()
+        .find(|subject| subject.name == subject_name)
+        .unwrap();
+      for course in subject.courses.iter_mut().flatten() {
+        course.is_hidden_by_user = hidden;
+      }
+      update_subjects(new_subjects);
+    }
+  };
+  let on_remove_subject = {
+    let subjects = subjects.clone();
+    let update_subjects = update_subjects.clone();
+    move |subject_name: String| {
+      let mut new_subjects = (*subjects).clone();
+      new_subjects.retain(|subject| subject.name != subject_name);
+      update_subjects(new_subjects);
+    }
+  };
+
+  let on_upload = {
+    let subjects = subjects.clone();
+    let upload_reports = upload_reports.clone();
+    let update_subjects = update_subjects.clone();
+    move |uploaded_subjects: Vec<Subject>| {
+      let mut new_subjects = (*subjects).clone();
+      let mut reports = vec![];
+      for uploaded in uploaded_subjects {
+        // a re-uploaded subject is recognized by its courses, it could have been renamed since
+        let existing = new_subjects
+          .iter_mut()
+          .find(|subject| subject.shares_courses_with(&uploaded) || subject.name == uploaded.name);
+        match existing {
+          Some(subject) => {
+            let report = subject.merge(uploaded);
+            reports.push(merge_report_message(&subject.name, &report));
+          }
+          None => {
+            reports.push(format!("{}: added", uploaded.name));
+            new_subjects.push(uploaded);
+          }
+        }
+      }
+      upload_reports.set(reports);
+      update_subjects(new_subjects);
+    }
+  };
+
+  let on_add_subject = {
     let subjects = subjects.clone();
     let update_subjects = update_subjects.clone();
-    move |course_code: String| {
+    move |name: String| {
       let mut new_subjects = (*subjects).clone();
-      new_subjects.update_subjects_by_course_code(
-        course_code.clone(),
-        Box::new(|course| course.is_deleted = true),
-      );
+      new_subjects.push(Subject {
+        name,
+        courses: vec![],
+      });
       update_subjects(new_subjects);
     }
   };
-  let on_toggle_visibility = {
+  let on_duplicate_subject = {
     let subjects = subjects.clone();
     let update_subjects = update_subjects.clone();
-    move |course_code: String| {
+    move |subject_name: String| {
       let mut new_subjects = (*subjects).clone();
-      new_subjects.update_subjects_by_course_code(
-        course_code.clone(),
-        Box::new(|course| course.is_hidden_by_user = !course.is_hidden_by_user),
-      );
+      let subject = new_subjects
+        .iter()
+        .find(|subject| subject.name == subject_name)
+        .unwrap();
+      let copy = duplicate_subject(subject, &new_subjects);
+      new_subjects.push(copy);
       update_subjects(new_subjects);
     }
   };
+  let on_rename_subject = {
+    let subjects = subjects.clone();
+    let update_subjects = update_subjects.clone();
+    move |(old_name, new_name): (String, String)| {
+      let mut new_subjects = (*subjects).clone();
+      let subject = new_subjects
+        .iter_mut()
+        .find(|subject| subject.name == old_name)
+        .unwrap();
+      subject.name = new_name.clone();
+      for course in subject.courses.iter_mut().flatten() {
+        course.subject_name = new_name.clone();
+        course.update_hash();
+      }
+      update_subjects(new_subjects);
+    }
+  };
+  let on_save_course = {
+    let subjects = subjects.clone();
+    let update_subjects = update_subjects.clone();
+    move |(subject_name, original_code, course): (String, Option<String>, Course)| {
+      let mut new_subjects = (*subjects).clone();
+      let subject = new_subjects
+        .iter_mut()
+        .find(|subject| subject.name == subject_name)
+        .unwrap();
+      match original_code {
+        Some(code) => subject.replace_course(&code, course),
+        None => subject.add_course(course),
+      }
+      update_subjects(new_subjects);
+    }
+  };
+
+  let subject_callbacks = SubjectCallbacks {
+    on_delete: update_course(|course| course.is_deleted = true),
+    on_toggle_visibility: update_course(|course| {
+      course.is_hidden_by_user = !course.is_hidden_by_user
+    }),
+    on_restore: update_course(|course| course.is_deleted = false),
+    on_set_group_hidden: on_set_group_hidden.into(),
+    on_set_subject_hidden: on_set_subject_hidden.into(),
+    on_remove_subject: on_remove_subject.into(),
+    on_add_subject: on_add_subject.into(),
+    on_duplicate_subject: on_duplicate_subject.into(),
+    on_rename_subject: on_rename_subject.into(),
+    on_save_course: on_save_course.into(),
+  };
+
+  let update_filters = {
+    let filters = filters.clone();
+    move |new_filters: Vec<String>| {
+      storage::save_filters(&new_filters);
+      filters.set(new_filters);
+    }
+  };
 
   html! {
     <main class="min-h-screen bg-gray-800 text-white">
+      <ScenariosComponent
+        names={(*scenario_names).clone()}
+        current={(*current_scenario).clone()}
+        on_change={on_scenarios_change}
+      />
       <label>{ "Subjects:" }</label>
-      <UploadComponent on_files_processed={update_subjects}/>
-      <SubjectsComponent
+      <UploadComponent on_files_processed={on_upload}/>
+      { for upload_reports.iter().map(|report| html! { <p>{ report }</p> }) }
+      <button onclick={on_undo} disabled={history.is_empty()}>{ "Undo" }</button>
+      <SubjectsComponent subjects={(*subjects).clone()} callbacks={subject_callbacks} />
+      <FilterBuilderComponent
         subjects={(*subjects).clone()}
-        on_delete={on_delete.clone()}
-        on_toggle_visibility={on_toggle_visibility.clone()}
+        filters={(*filters).clone()}
+        on_change={update_filters}
+      />
+      <TimetableGenerator
+        subjects={(*subjects).clone()}
+        filters={(*filters).clone()}
+        shared_courses={(*shared).as_ref().map(|shared| shared.courses.clone())}
       />
-      <StatisticsComponent subjects={(*subjects).clone()} />
-      <TimetableGenerator subjects={(*subjects).clone()} />
     </main>
   }
 }
+
+fn load_shared_timetable() -> Option<SharedTimetable> {
+  let hash = gloo::utils::window().location().hash().ok()?;
+  let encoded = hash.trim_start_matches('#');
+  if encoded.is_empty() {
+    return None;
+  }
+  share::decode(encoded).ok()
+}
+
+/// Adds a scenario with the subjects of the current one and the filters of the shared timetable.
+/// The link is removed from the address, so reloading the page doesn't add it again.
+fn open_shared_timetable() -> Option<SharedTimetable> {
+  let shared = load_shared_timetable()?;
+  let window = gloo::utils::window();
+  let location = window.location();
+  let url = format!(
+    "{}{}",
+    location.pathname().unwrap(),
+    location.search().unwrap()
+  );
+  window
+    .history()
+    .unwrap()
+    .replace_state_with_url(&JsValue::NULL, "", Some(&url))
+    .unwrap();
+
+  let current = storage::load_current_scenario();
+  scenarios::add_scenario(Scenario {
+    name: scenarios::unique_name("Shared timetable", &scenario_names()),
+    subjects: current.subjects,
+    filters: shared.filters.clone(),
+  });
+  Some(shared)
+}
+
+fn scenario_names() -> Vec<String> {
+  storage::load_scenarios()
+    .into_iter()
+    .map(|scenario| scenario.name)
+    .collect()
+}
+
+/// A copy of the subject with a new name, the course codes get a suffix so they stay unique.
+fn duplicate_subject(subject: &Subject, subjects: &[Subject]) -> Subject {
+  let is_taken = |suffix: &str| {
+    subjects.iter().any(|other| {
+      other.name == format!("{}{suffix}", subject.name)
+        || subject.courses.iter().flatten().any(|course| {
+          let code = format!("{}{suffix}", course.code);
+          other
+            .courses
+            .iter()
+            .flatten()
+            .any(|other| other.code == code)
+        })
+    })
+  };
+  let suffix = (1..)
+    .map(|i| format!(" copy {i}"))
+    .find(|suffix| !is_taken(suffix))
+    .unwrap();
+
+  let mut copy = subject.clone();
+  copy.name = format!("{}{suffix}", subject.name);
+  for course in copy.courses.iter_mut().flatten() {
+    course.code = format!("{}{suffix}", course.code);
+    course.subject_name = copy.name.clone();
+    course.update_hash();
+  }
+  copy
+}
+
+fn merge_report_message(subject_name: &str, report: &MergeReport) -> String {
+  let list = |codes: &[String]| match codes {
+    [] => "none".to_string(),
+    codes => codes.join(", "),
+  };
+  format!(
+    "{subject_name}: added {}; removed {}; changed {}; kept added by hand {}",
+    list(&report.added),
+    list(&report.removed),
+    list(&report.changed),
+    list(&report.kept)
+  )
+}
@@ -0,0 +1,201 @@
+use gloo::{
+  file::{callbacks::FileReader, Blob, ObjectUrl},
+  timers::callback::Timeout,
+};
+use web_sys::{wasm_bindgen::JsCast, HtmlAnchorElement, HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::storage::{self, Scenario};
+
+#[derive(Properties, PartialEq)]
+pub struct ScenariosProps {
+  pub names: Vec<String>,
+  pub current: String,
+  /// Called after the scenarios in the storage changed, the app reloads the current one.
+  pub on_change: Callback<()>,
+}
+
+#[function_component(ScenariosComponent)]
+pub fn scenarios_component(props: &ScenariosProps) -> Html {
+  let new_name = use_state(|| None::<String>);
+  let error = use_state(|| None::<String>);
+  let import_reader = use_mut_ref(|| None::<FileReader>);
+
+  let on_switch = {
+    let on_change = props.on_change.clone();
+    move |e: Event| {
+      let select: HtmlSelectElement = e.target_unchecked_into();
+      storage::save_current_scenario_name(&select.value());
+      on_change.emit(());
+    }
+  };
+
+  let on_new = {
+    let names = props.names.clone();
+    let on_change = props.on_change.clone();
+    move |_| {
+      add_scenario(Scenario {
+        name: unique_name("Scenario", &names),
+        subjects: vec![],
+        filters: vec![],
+      });
+      on_change.emit(());
+    }
+  };
+
+  let on_duplicate = {
+    let names = props.names.clone();
+    let on_change = props.on_change.clone();
+    move |_| {
+      let mut scenario = storage::load_current_scenario();
+      scenario.name = unique_name(&format!("{} copy", scenario.name), &names);
+      add_scenario(scenario);
+      on_change.emit(());
+    }
+  };
+
+  let on_delete = {
+    let current = props.current.clone();
+    let on_change = props.on_change.clone();
+    move |_| {
+      let mut scenarios = storage::load_scenarios();
+      scenarios.retain(|scenario| scenario.name != current);
+      storage::save_current_scenario_name(&scenarios[0].name);
+      storage::save_scenarios(&scenarios);
+      on_change.emit(());
+    }
+  };
+
+  let on_start_rename = {
+    let new_name = new_name.clone();
+    let current = props.current.clone();
+    move |_| new_name.set(Some(current.clone()))
+  };
+  let on_rename_input = {
+    let new_name = new_name.clone();
+    move |e: InputEvent| {
+      let input: HtmlInputElement = e.target_unchecked_into();
+      new_name.set(Some(input.value()));
+    }
+  };
+  let on_rename = {
+    let new_name = new_name.clone();
+    let error = error.clone();
+    let names = props.names.clone();
+    let current = props.current.clone();
+    let on_change = props.on_change.clone();
+    move |_| {
+      let name = (*new_name).clone().unwrap_or_default().trim().to_string();
+      if name.is_empty() {
+        error.set(Some("The scenario name is required".to_string()));
+      } else if name != current && names.contains(&name) {
+        error.set(Some(format!("There is already a scenario named {name}")));
+      } else {
+        let mut scenarios = storage::load_scenarios();
+        for scenario in scenarios.iter_mut().filter(|s| s.name == current) {
+          scenario.name = name.clone();
+        }
+        storage::save_scenarios(&scenarios);
+        storage::save_current_scenario_name(&name);
+        new_name.set(None);
+        error.set(None);
+        on_change.emit(());
+      }
+    }
+  };
+
+  let on_export = move |_| {
+    let scenario = storage::load_current_scenario();
+    let json = serde_json::to_string_pretty(&scenario).unwrap();
+    download(&format!("{}.json", scenario.name), &json);
+  };
+
+  let on_import = {
+    let names = props.names.clone();
+    let error = error.clone();
+    let on_change = props.on_change.clone();
+    move |e: Event| {
+      let input: HtmlInputElement = e.target_unchecked_into();
+      let Some(file) = input.files().and_then(|files| files.get(0)) else {
+        return;
+      };
+      let names = names.clone();
+      let error = error.clone();
+      let on_change = on_change.clone();
+      let reader = gloo::file::callbacks::read_as_text(&file.into(), move |text| {
+        let scenario = text
+          .map_err(|err| err.to_string())
+          .and_then(|text| serde_json::from_str::<Scenario>(&text).map_err(|err| err.to_string()));
+        match scenario {
+          Ok(mut scenario) => {
+            scenario.name = unique_name(&scenario.name, &names);
+            add_scenario(scenario);
+            error.set(None);
+            on_change.emit(());
+          }
+          Err(err) => error.set(Some(format!("Failed to import the scenario: {err}"))),
+        }
+      });
+      *import_reader.borrow_mut() = Some(reader);
+    }
+  };
+
+  html! {
+    <div class="flex flex-wrap gap-2">
+      <label>{ "Scenario:" }</label>
+      if let Some(name) = &*new_name {
+        <input type="text" value={name.clone()} oninput={on_rename_input} />
+        <button onclick={on_rename}>{ "Save name" }</button>
+      } else {
+        <select onchange={on_switch}>
+          { for props.names.iter().map(|name| html! {
+            <option selected={*name == props.current}>{ name }</option>
+          }) }
+        </select>
+        <button onclick={on_start_rename}>{ "Rename" }</button>
+      }
+      <button onclick={on_new}>{ "New" }</button>
+      <button onclick={on_duplicate}>{ "Duplicate" }</button>
+      <button onclick={on_delete} disabled={props.names.len() <= 1}>{ "Delete" }</button>
+      <button onclick={on_export}>{ "Export" }</button>
+      <label>{ "Import:" }</label>
+      <input type="file" accept=".json" onchange={on_import} />
+      if let Some(error) = &*error {
+        <p>{ error }</p>
+      }
+    </div>
+  }
+}
+
+/// Adds the scenario and switches to it.
+pub fn add_scenario(scenario: Scenario) {
+  let mut scenarios = storage::load_scenarios();
+  storage::save_current_scenario_name(&scenario.name);
+  scenarios.push(scenario);
+  storage::save_scenarios(&scenarios);
+}
+
+pub fn unique_name(name: &str, taken_names: &[String]) -> String {
+  if !taken_names.iter().any(|taken| taken == name) {
+    return name.to_string();
+  }
+  (2..)
+    .map(|i| format!("{name} {i}"))
+    .find(|name| !taken_names.contains(name))
+    .unwrap()
+}
+
+/// Lets the browser download the text as a file.
+fn download(file_name: &str, text: &str) {
+  let blob = Blob::new_with_options(text, Some("application/json"));
+  let url = ObjectUrl::from(blob);
+  let anchor: HtmlAnchorElement = gloo::utils::document()
+    .create_element("a")
+    .unwrap()
+    .unchecked_into();
+  anchor.set_href(&url);
+  anchor.set_download(file_name);
+  anchor.click();
+  // the url has to outlive the start of the download
+  Timeout::new(1000, move || drop(url)).forget();
+}
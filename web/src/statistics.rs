@@ -1,19 +1,135 @@
-use timetable_optimizer_lib::{data::Subject, stats};
+use std::collections::BTreeMap;
+
+use chrono::NaiveTime;
+use itertools::Itertools;
+use timetable_optimizer_lib::{
+  bottleneck::{self, Conflicts, Unlock},
+  data::Subject,
+  generator::TimetableCount,
+  stats::{self, TimetableStats},
+};
 use yew::prelude::*;
 
 #[derive(Properties, PartialEq)]
 pub struct StatisticsProps {
   pub subjects: Vec<Subject>,
+  pub filters: Vec<String>,
+  /// Timetables without clashes found so far.
+  pub valid: u64,
+  /// Timetables without clashes, `None` until they are counted.
+  pub valid_count: Option<TimetableCount>,
+  /// Timetables without clashes left after each filter updating the courses, with the filter's index.
+  pub course_funnel: Vec<(usize, TimetableCount)>,
+  /// Timetables left after each filter.
+  pub funnel: Vec<u64>,
+  /// Statistics of the timetables found so far.
+  pub stats: TimetableStats,
+  /// Conflicting groups and unlocking courses, `None` unless there are few timetables.
+  pub bottlenecks: Option<(Conflicts, Vec<Unlock>)>,
 }
 
 #[function_component(StatisticsComponent)]
 pub fn statistics_component(props: &StatisticsProps) -> Html {
+  let timetable_stats = &props.stats;
+  // the filters updating the courses take effect first, before the timetables are generated
+  let course_steps = props
+    .course_funnel
+    .iter()
+    .filter_map(|&(i, count)| Some((props.filters.get(i)?, count.to_string())));
+  let timetable_steps = props
+    .filters
+    .iter()
+    .zip(&props.funnel)
+    .enumerate()
+    .filter(|(i, _)| !props.course_funnel.iter().any(|(filter, _)| filter == i))
+    .map(|(_, (spec, count))| (spec, count.to_string()));
+  let format_time = |time: &NaiveTime| time.format("%H:%M").to_string();
   html! {
     <>
       <h1>{ "Statistics" }</h1>
       <p>{ format!("Total courses inputted: {}", stats::count_all_courses(&props.subjects)) }</p>
       <p>{ format!("Total courses in a timetable: {}", stats::count_course_per_timetable(&props.subjects)) }</p>
-      <p>{ format!("Total possible timetables: {}", stats::count_all_timetables(&props.subjects)) }</p>
+      <p>{ format!("Total possible timetables: {}", count_text(stats::count_all_timetables(&props.subjects))) }</p>
+      if let Some(valid_count) = props.valid_count {
+        <p>{ format!("Timetables without clashes: {valid_count}") }</p>
+      } else {
+        <p>{ "Timetables without clashes: counting..." }</p>
+      }
+      <p>{ format!("Timetables without clashes found so far: {}", props.valid) }</p>
+      <ol>
+        { for course_steps.chain(timetable_steps).map(|(spec, count)| html! {
+          <li>{ format!("{spec}: {count}") }</li>
+        }) }
+      </ol>
+      if let Some((conflicts, unlocks)) = &props.bottlenecks {
+        <h2>{ "Bottlenecks" }</h2>
+        if conflicts.is_incomplete {
+          <p>{ format!("No conflicting groups found, only up to {} groups were searched", bottleneck::MAX_CONFLICT_SIZE) }</p>
+        } else if conflicts.sets.is_empty() {
+          <p>{ "No conflicting groups" }</p>
+        } else {
+          <p>{ "Groups that can't be in a timetable together:" }</p>
+          <ul>
+            { for conflicts.sets.iter().map(|conflict| html! {
+              <li>{ conflict.iter().join(", ") }</li>
+            }) }
+          </ul>
+        }
+        if !unlocks.is_empty() {
+          <p>{ "Courses adding timetables if re-enabled:" }</p>
+          <ul>
+            { for unlocks.iter().map(|unlock| html! {
+              <li>{ format!("{} ({}): {} timetables", unlock.code, unlock.subject_name, unlock.timetable_count) }</li>
+            }) }
+          </ul>
+        }
+      }
+      if timetable_stats.count > 0 {
+        { histogram("Free workdays", &timetable_stats.free_workdays, usize::to_string) }
+        { histogram("Earliest start", &timetable_stats.earliest_start, format_time) }
+        { histogram("Latest end", &timetable_stats.latest_end, format_time) }
+        { histogram("Total gap hours", &timetable_stats.total_gap_hours, i64::to_string) }
+        <h2>{ "Course frequency" }</h2>
+        <table>
+          { for timetable_stats.course_frequency.keys().map(|(subject_name, code)| html! {
+            <tr>
+              <td>{ format!("{code} ({subject_name})") }</td>
+              <td>{ format!("{:.0}%", timetable_stats.course_percentage(subject_name, code)) }</td>
+            </tr>
+          }) }
+        </table>
+      }
+    </>
+  }
+}
+
+fn histogram<K>(
+  title: &str,
+  histogram: &BTreeMap<K, usize>,
+  format_key: impl Fn(&K) -> String,
+) -> Html {
+  let max = histogram.values().max().copied().unwrap_or(1);
+  html! {
+    <>
+      <h2>{ title }</h2>
+      <table>
+        { for histogram.iter().map(|(key, &count)| html! {
+          <tr>
+            <td>{ format_key(key) }</td>
+            <td>{ count }</td>
+            <td>
+              <div class="h-3 bg-gray-400" style={format!("width: {}px;", count * 200 / max)} />
+            </td>
+          </tr>
+        }) }
+      </table>
     </>
   }
 }
+
+fn count_text(count: Option<u64>) -> String {
+  match count {
+    Some(count) => count.to_string(),
+    None => "too many to count".to_string(),
+  }
+}
@@ -1,14 +1,84 @@
 use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
 use timetable_optimizer_lib::data::Subject;
 
-pub fn save_subjects(subjects: &Vec<Subject>) {
-  let subjects = serde_json::to_string(subjects).unwrap();
-  LocalStorage::set("subjects", subjects).unwrap();
+const DEFAULT_SCENARIO_NAME: &str = "Default";
+
+/// A named set of subjects and filters, the subjects and filters are saved to the current one.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct Scenario {
+  pub name: String,
+  pub subjects: Vec<Subject>,
+  pub filters: Vec<String>,
+}
+
+pub fn load_scenarios() -> Vec<Scenario> {
+  match LocalStorage::get::<String>("scenarios") {
+    Ok(scenarios) => serde_json::from_str::<Vec<Scenario>>(&scenarios).unwrap(),
+    // the subjects and filters saved before there were scenarios
+    Err(_) => vec![Scenario {
+      name: DEFAULT_SCENARIO_NAME.to_string(),
+      subjects: load_legacy("subjects"),
+      filters: load_legacy("filters"),
+    }],
+  }
+}
+
+pub fn save_scenarios(scenarios: &[Scenario]) {
+  let scenarios = serde_json::to_string(scenarios).unwrap();
+  LocalStorage::set("scenarios", scenarios).unwrap();
+}
+
+pub fn load_current_scenario_name() -> String {
+  let name = LocalStorage::get::<String>("current_scenario").ok();
+  let scenarios = load_scenarios();
+  name
+    .filter(|name| scenarios.iter().any(|scenario| &scenario.name == name))
+    .unwrap_or_else(|| scenarios[0].name.clone())
+}
+
+pub fn save_current_scenario_name(name: &str) {
+  LocalStorage::set("current_scenario", name).unwrap();
+}
+
+pub fn load_current_scenario() -> Scenario {
+  let name = load_current_scenario_name();
+  load_scenarios()
+    .into_iter()
+    .find(|scenario| scenario.name == name)
+    .unwrap()
+}
+
+fn update_current_scenario(update_fn: impl FnOnce(&mut Scenario)) {
+  let name = load_current_scenario_name();
+  let mut scenarios = load_scenarios();
+  let scenario = scenarios
+    .iter_mut()
+    .find(|scenario| scenario.name == name)
+    .unwrap();
+  update_fn(scenario);
+  save_scenarios(&scenarios);
+}
+
+pub fn save_subjects(subjects: &[Subject]) {
+  update_current_scenario(|scenario| scenario.subjects = subjects.to_vec());
 }
 
 pub fn load_subjects() -> Vec<Subject> {
-  match LocalStorage::get::<String>("subjects") {
-    Ok(subjects) => serde_json::from_str::<Vec<Subject>>(&subjects).unwrap(),
+  load_current_scenario().subjects
+}
+
+pub fn save_filters(filters: &[String]) {
+  update_current_scenario(|scenario| scenario.filters = filters.to_vec());
+}
+
+pub fn load_filters() -> Vec<String> {
+  load_current_scenario().filters
+}
+
+fn load_legacy<T: for<'de> Deserialize<'de>>(key: &str) -> Vec<T> {
+  match LocalStorage::get::<String>(key) {
+    Ok(values) => serde_json::from_str::<Vec<T>>(&values).unwrap(),
     Err(_) => vec![],
   }
 }
@@ -1,43 +1,282 @@
 use crate::course::CourseComponent;
-use timetable_optimizer_lib::data::{Course, Subject};
+use crate::course_editor::{new_course, CourseEditorComponent};
+use timetable_optimizer_lib::data::{Course, OneOfCourse, Subject};
+use web_sys::HtmlInputElement;
 use yew::prelude::*;
 
+/// The edits of the subject list, each is applied to the subjects by the app.
+#[derive(PartialEq, Clone)]
+pub struct SubjectCallbacks {
+  pub on_delete: Callback<String>,
+  pub on_toggle_visibility: Callback<String>,
+  pub on_restore: Callback<String>,
+  /// Subject name, index of the group and whether its courses are hidden.
+  pub on_set_group_hidden: Callback<(String, usize, bool)>,
+  /// Subject name and whether its courses are hidden.
+  pub on_set_subject_hidden: Callback<(String, bool)>,
+  pub on_remove_subject: Callback<String>,
+  pub on_add_subject: Callback<String>,
+  pub on_duplicate_subject: Callback<String>,
+  /// The old and the new name of the subject.
+  pub on_rename_subject: Callback<(String, String)>,
+  /// Subject name, the code of the edited course or `None` for a new one and the saved course.
+  pub on_save_course: Callback<(String, Option<String>, Course)>,
+}
+
 #[derive(Properties, PartialEq)]
 pub struct SubjectsProps {
   pub subjects: Vec<Subject>,
-  pub on_delete: Callback<String>,
-  pub on_toggle_visibility: Callback<String>,
+  pub callbacks: SubjectCallbacks,
 }
 
 #[function_component(SubjectsComponent)]
 pub fn subjects_component(props: &SubjectsProps) -> Html {
+  let new_subject_name = use_state(String::new);
+  let error = use_state(|| None::<String>);
+
+  let subject_names: Vec<String> = props.subjects.iter().map(|s| s.name.clone()).collect();
+  let course_codes: Vec<String> = props
+    .subjects
+    .iter()
+    .flat_map(|subject| &subject.courses)
+    .flatten()
+    .map(|course| course.code.clone())
+    .collect();
+  let deleted_courses: Vec<&Course> = props
+    .subjects
+    .iter()
+    .flat_map(|subject| &subject.courses)
+    .flatten()
+    .filter(|course| course.is_deleted)
+    .collect();
+
+  let on_name_input = {
+    let new_subject_name = new_subject_name.clone();
+    move |e: InputEvent| {
+      let input: HtmlInputElement = e.target_unchecked_into();
+      new_subject_name.set(input.value());
+    }
+  };
+  let on_add_subject = {
+    let new_subject_name = new_subject_name.clone();
+    let error = error.clone();
+    let subject_names = subject_names.clone();
+    let on_add_subject = props.callbacks.on_add_subject.clone();
+    move |_| match validate_subject_name(&new_subject_name, &subject_names) {
+      Ok(name) => {
+        on_add_subject.emit(name);
+        new_subject_name.set(String::new());
+        error.set(None);
+      }
+      Err(e) => error.set(Some(e)),
+    }
+  };
+
   html! {
-    for props.subjects.iter().map(|subject| {
-      html! {
-        <SubjectComponent
-          subject={subject.clone()}
-          on_delete={props.on_delete.clone()}
-          on_toggle_visibility={props.on_toggle_visibility.clone()}
-        />
+    <>
+      { for props.subjects.iter().map(|subject| {
+        html! {
+          <SubjectComponent
+            subject={subject.clone()}
+            subject_names={subject_names.clone()}
+            course_codes={course_codes.clone()}
+            callbacks={props.callbacks.clone()}
+          />
+        }
+      }) }
+      <div class="flex gap-2">
+        <input type="text" value={(*new_subject_name).clone()} oninput={on_name_input} />
+        <button onclick={on_add_subject}>{ "New subject" }</button>
+      </div>
+      if let Some(error) = &*error {
+        <p>{ error }</p>
       }
-    })
+      if !deleted_courses.is_empty() {
+        <div class="my-6">
+          <h2>{ "Deleted courses" }</h2>
+          <table>
+            <tbody>
+              { for deleted_courses.into_iter().map(|course| {
+                course_row(course, &props.callbacks, Callback::noop(), Callback::noop())
+              }) }
+            </tbody>
+          </table>
+        </div>
+      }
+    </>
   }
 }
 
 #[derive(Properties, PartialEq)]
 pub struct SubjectProps {
   pub subject: Subject,
-  pub on_delete: Callback<String>,
-  pub on_toggle_visibility: Callback<String>,
+  /// Names of all the subjects, subject names have to be unique.
+  pub subject_names: Vec<String>,
+  /// Codes of the courses of all the subjects.
+  pub course_codes: Vec<String>,
+  pub callbacks: SubjectCallbacks,
+}
+
+/// A course opened in the course editor.
+#[derive(Clone)]
+struct Editing {
+  /// `None` for a course that doesn't exist yet.
+  original_code: Option<String>,
+  course: Course,
 }
 
 #[function_component(SubjectComponent)]
 pub fn subject_component(props: &SubjectProps) -> Html {
-  let courses: Vec<&Course> = props.subject.courses.iter().flatten().collect();
+  let subject = &props.subject;
+  let callbacks = &props.callbacks;
+  let editing = use_state(|| None::<Editing>);
+  let renaming = use_state(|| None::<String>);
+  let rename_error = use_state(|| None::<String>);
+
+  let set_subject_hidden = |hidden: bool| {
+    let name = subject.name.clone();
+    let on_set_subject_hidden = callbacks.on_set_subject_hidden.clone();
+    move |_| on_set_subject_hidden.emit((name.clone(), hidden))
+  };
+  let on_remove_subject = {
+    let name = subject.name.clone();
+    let on_remove_subject = callbacks.on_remove_subject.clone();
+    move |_| on_remove_subject.emit(name.clone())
+  };
+  let on_duplicate_subject = {
+    let name = subject.name.clone();
+    let on_duplicate_subject = callbacks.on_duplicate_subject.clone();
+    move |_| on_duplicate_subject.emit(name.clone())
+  };
+
+  let on_start_rename = {
+    let renaming = renaming.clone();
+    let name = subject.name.clone();
+    move |_| renaming.set(Some(name.clone()))
+  };
+  let on_rename_input = {
+    let renaming = renaming.clone();
+    move |e: InputEvent| {
+      let input: HtmlInputElement = e.target_unchecked_into();
+      renaming.set(Some(input.value()));
+    }
+  };
+  let on_rename = {
+    let renaming = renaming.clone();
+    let rename_error = rename_error.clone();
+    let name = subject.name.clone();
+    let other_names: Vec<String> = props
+      .subject_names
+      .iter()
+      .filter(|other| **other != subject.name)
+      .cloned()
+      .collect();
+    let on_rename_subject = callbacks.on_rename_subject.clone();
+    move |_| {
+      let new_name = (*renaming).clone().unwrap_or_default();
+      match validate_subject_name(&new_name, &other_names) {
+        Ok(new_name) => {
+          on_rename_subject.emit((name.clone(), new_name));
+          renaming.set(None);
+          rename_error.set(None);
+        }
+        Err(e) => rename_error.set(Some(e)),
+      }
+    }
+  };
+
+  let find_course = {
+    let subject = subject.clone();
+    move |code: &str| {
+      subject
+        .courses
+        .iter()
+        .flatten()
+        .find(|course| course.code == code)
+        .unwrap()
+        .clone()
+    }
+  };
+  let on_new_course = {
+    let editing = editing.clone();
+    let name = subject.name.clone();
+    move |_| {
+      editing.set(Some(Editing {
+        original_code: None,
+        course: new_course(&name),
+      }))
+    }
+  };
+  let on_edit = {
+    let editing = editing.clone();
+    let find_course = find_course.clone();
+    Callback::from(move |code: String| {
+      editing.set(Some(Editing {
+        course: find_course(&code),
+        original_code: Some(code),
+      }))
+    })
+  };
+  let on_duplicate = {
+    let editing = editing.clone();
+    Callback::from(move |code: String| {
+      editing.set(Some(Editing {
+        original_code: None,
+        course: find_course(&code).duplicate(),
+      }))
+    })
+  };
+
+  let editor = (*editing).clone().map(
+    |Editing {
+       original_code,
+       course,
+     }| {
+      let taken_codes: Vec<String> = props
+        .course_codes
+        .iter()
+        .filter(|&code| Some(code) != original_code.as_ref())
+        .cloned()
+        .collect();
+      let on_save = {
+        let editing = editing.clone();
+        let name = subject.name.clone();
+        let original_code = original_code.clone();
+        let on_save_course = callbacks.on_save_course.clone();
+        move |course| {
+          on_save_course.emit((name.clone(), original_code.clone(), course));
+          editing.set(None);
+        }
+      };
+      let on_cancel = {
+        let editing = editing.clone();
+        move |_| editing.set(None)
+      };
+      html! {
+        <CourseEditorComponent {course} {taken_codes} {on_save} {on_cancel} />
+      }
+    },
+  );
+
   html! {
     <div class="my-6">
-      <h2>{ &props.subject.name }</h2>
-      if !courses.is_empty() {
+      if let Some(new_name) = &*renaming {
+        <input type="text" value={new_name.clone()} oninput={on_rename_input} />
+        <button onclick={on_rename}>{ "Save name" }</button>
+        if let Some(error) = &*rename_error {
+          <p>{ error }</p>
+        }
+      } else {
+        <h2>{ &subject.name }</h2>
+        <button onclick={on_start_rename}>{ "Rename" }</button>
+      }
+      <button onclick={set_subject_hidden(true)}>{ "Hide all" }</button>
+      <button onclick={set_subject_hidden(false)}>{ "Show all" }</button>
+      <button onclick={on_duplicate_subject}>{ "Duplicate subject" }</button>
+      <button onclick={on_remove_subject}>{ "Remove subject" }</button>
+      <button onclick={on_new_course}>{ "New course" }</button>
+      { for editor }
+      if subject.courses.iter().flatten().any(|course| !course.is_deleted) {
         <table>
           <thead>
             <tr>
@@ -49,15 +288,78 @@ pub fn subject_component(props: &SubjectProps) -> Html {
               <th>{ "Teacher" }</th>
             </tr>
           </thead>
-          <tbody>
-            { for courses.into_iter().filter(|course| !course.is_deleted).map(|course| {
-              html! {
-                <CourseComponent course={course.clone()} on_delete={props.on_delete.clone()} on_toggle_visibility={props.on_toggle_visibility.clone()} />
-              }
-            }) }
-          </tbody>
+          { for subject.courses.iter().enumerate().map(|(i, one_of_course)| {
+            group_rows(&subject.name, i, one_of_course, callbacks, &on_edit, &on_duplicate)
+          }) }
         </table>
       }
     </div>
   }
 }
+
+fn validate_subject_name(name: &str, taken_names: &[String]) -> Result<String, String> {
+  let name = name.trim();
+  if name.is_empty() {
+    Err("The subject name is required".to_string())
+  } else if taken_names.iter().any(|taken| taken == name) {
+    Err(format!("There is already a subject named {name}"))
+  } else {
+    Ok(name.to_string())
+  }
+}
+
+fn group_rows(
+  subject_name: &str,
+  index: usize,
+  one_of_course: &OneOfCourse,
+  callbacks: &SubjectCallbacks,
+  on_edit: &Callback<String>,
+  on_duplicate: &Callback<String>,
+) -> Html {
+  let courses: Vec<&Course> = one_of_course
+    .iter()
+    .filter(|course| !course.is_deleted)
+    .collect();
+  if courses.is_empty() {
+    return html! {};
+  }
+
+  let set_group_hidden = |hidden: bool| {
+    let name = subject_name.to_string();
+    let on_set_group_hidden = callbacks.on_set_group_hidden.clone();
+    move |_| on_set_group_hidden.emit((name.clone(), index, hidden))
+  };
+
+  html! {
+    <tbody>
+      <tr>
+        <td>
+          <button onclick={set_group_hidden(true)}>{ "Hide group" }</button>
+          <button onclick={set_group_hidden(false)}>{ "Show group" }</button>
+        </td>
+        <td colspan="5">{ format!("{} group", courses[0].course_type) }</td>
+      </tr>
+      { for courses.into_iter().map(|course| {
+        course_row(course, callbacks, on_edit.clone(), on_duplicate.clone())
+      }) }
+    </tbody>
+  }
+}
+
+fn course_row(
+  course: &Course,
+  callbacks: &SubjectCallbacks,
+  on_edit: Callback<String>,
+  on_duplicate: Callback<String>,
+) -> Html {
+  html! {
+    <CourseComponent
+      course={course.clone()}
+      on_delete={callbacks.on_delete.clone()}
+      on_toggle_visibility={callbacks.on_toggle_visibility.clone()}
+      on_restore={callbacks.on_restore.clone()}
+      {on_edit}
+      {on_duplicate}
+    />
+  }
+}
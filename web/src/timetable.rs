@@ -0,0 +1,322 @@
+use chrono::{NaiveTime, Weekday};
+use timetable_optimizer_lib::{
+  data::{Course, Subject, Timetable},
+  filter::{self, Filter},
+  generator::TimetableGenerator,
+  share::{self, SharedTimetable},
+};
+use yew::prelude::*;
+
+use crate::timetable_generator::FoundTimetables;
+
+// the same layout as the exported images of the cli
+const HEADER_HEIGHT: u32 = 50;
+const TIMES_WIDTH: u32 = 100;
+const DAY_WIDTH: u32 = 150;
+const MINUTE_HEIGHT: f32 = 1f32;
+const DAYS: &[Weekday] = &[
+  Weekday::Mon,
+  Weekday::Tue,
+  Weekday::Wed,
+  Weekday::Thu,
+  Weekday::Fri,
+  Weekday::Sat,
+];
+const DAY_START_HOUR: u32 = 8;
+const DAY_END_HOUR: u32 = 20;
+
+#[derive(Properties, PartialEq)]
+pub struct TimetableViewerProps {
+  pub subjects: Vec<Subject>,
+  pub filters: Vec<String>,
+  /// Combination indices of the found timetables.
+  pub timetables: FoundTimetables,
+  /// Subject names and course codes of a timetable from a shared link, it's shown once it's generated.
+  pub shared_courses: Option<Vec<(String, String)>>,
+}
+
+#[function_component(TimetableViewerComponent)]
+pub fn timetable_viewer_component(props: &TimetableViewerProps) -> Html {
+  let position = use_state(|| 0);
+  let selected_course = use_state(|| None::<Course>);
+  let share_link = use_state(|| None::<String>);
+  let found_shared = use_state(|| false);
+
+  // the indices are only valid for the subjects the generator worker used
+  let subjects = use_memo(
+    (props.subjects.clone(), props.filters.clone()),
+    |(subjects, filters)| updated_subjects(subjects, filters),
+  );
+
+  {
+    let position = position.clone();
+    let selected_course = selected_course.clone();
+    use_effect_with((props.subjects.clone(), props.filters.clone()), move |_| {
+      position.set(0);
+      selected_course.set(None);
+    });
+  }
+
+  {
+    let position = position.clone();
+    let found_shared = found_shared.clone();
+    let subjects = subjects.clone();
+    let shared_courses = props.shared_courses.clone();
+    let timetables = props.timetables.clone();
+    use_effect_with(timetables.len(), move |_| {
+      let Some(shared_courses) = shared_courses.filter(|_| !*found_shared) else {
+        return;
+      };
+      let generator = TimetableGenerator::new(&subjects);
+      let shared_position = timetables.iter().position(|index| {
+        generator
+          .timetable_at(index)
+          .is_some_and(|timetable| has_courses(&timetable, &shared_courses))
+      });
+      if let Some(shared_position) = shared_position {
+        position.set(shared_position);
+        found_shared.set(true);
+      }
+    });
+  }
+
+  let missing_courses = props
+    .shared_courses
+    .iter()
+    .flatten()
+    .filter(|(subject_name, code)| {
+      !subjects
+        .iter()
+        .filter(|subject| &subject.name == subject_name)
+        .flat_map(|subject| &subject.courses)
+        .flatten()
+        .any(|course| &course.code == code)
+    })
+    .map(|(subject_name, code)| format!("{code} ({subject_name})"))
+    .collect::<Vec<_>>();
+  let missing_message = (!missing_courses.is_empty()).then(|| {
+    format!(
+      "The courses of the shared timetable are missing: {}",
+      missing_courses.join(", ")
+    )
+  });
+
+  if props.timetables.is_empty() {
+    return html! {
+      <>
+        <p>{ "No timetables to show." }</p>
+        if let Some(message) = missing_message {
+          <p>{ message }</p>
+        }
+      </>
+    };
+  }
+
+  let count = props.timetables.len();
+  let current = (*position).min(count - 1);
+
+  let on_previous = {
+    let position = position.clone();
+    move |_| position.set(current.saturating_sub(1))
+  };
+  let on_next = {
+    let position = position.clone();
+    move |_| position.set((current + 1).min(count - 1))
+  };
+
+  let on_select = {
+    let selected_course = selected_course.clone();
+    Callback::from(move |course| selected_course.set(Some(course)))
+  };
+  let on_close = {
+    let selected_course = selected_course.clone();
+    move |_| selected_course.set(None)
+  };
+
+  let generator = TimetableGenerator::new(&subjects);
+  let timetable = props
+    .timetables
+    .get(current)
+    .and_then(|index| generator.timetable_at(index));
+
+  let on_share = {
+    let share_link = share_link.clone();
+    let shared = SharedTimetable {
+      courses: timetable
+        .iter()
+        .flat_map(|timetable| &timetable.courses)
+        .map(|course| (course.subject_name.clone(), course.code.clone()))
+        .collect(),
+      filters: props.filters.clone(),
+    };
+    move |_| {
+      // the page keeps its own address, it would open the shared timetable again on reload
+      let location = gloo::utils::window().location();
+      share_link.set(Some(format!(
+        "{}{}{}#{}",
+        location.origin().unwrap(),
+        location.pathname().unwrap(),
+        location.search().unwrap(),
+        share::encode(&shared)
+      )));
+    }
+  };
+
+  html! {
+    <div>
+      <div class="flex gap-2 items-center">
+        <button onclick={on_previous} disabled={current == 0}>{ "Previous" }</button>
+        <span>{ format!("{} / {count}", current + 1) }</span>
+        <button onclick={on_next} disabled={current + 1 == count}>{ "Next" }</button>
+        <button onclick={on_share}>{ "Share" }</button>
+        if let Some(link) = &*share_link {
+          <input type="text" readonly=true value={link.clone()} />
+        }
+      </div>
+      if let Some(message) = missing_message {
+        <p>{ message }</p>
+      }
+      if let Some(timetable) = timetable {
+        { timetable_grid(&timetable, on_select) }
+      }
+      if let Some(course) = &*selected_course {
+        <div class="fixed top-1/4 left-1/3 p-4 bg-gray-700 border border-gray-400 rounded">
+          { course_details(course) }
+          <button onclick={on_close}>{ "Close" }</button>
+        </div>
+      }
+    </div>
+  }
+}
+
+fn updated_subjects(subjects: &[Subject], filters: &[String]) -> Vec<Subject> {
+  // invalid filters are reported by the generator
+  let filters = filters
+    .iter()
+    .filter_map(|spec| filter::parse_filter(spec).ok())
+    .collect::<Vec<Box<dyn Filter>>>();
+  let filter_refs = filters
+    .iter()
+    .map(|filter| filter.as_ref())
+    .collect::<Vec<_>>();
+  filter::update_courses(subjects, &filter_refs)
+}
+
+fn has_courses(timetable: &Timetable, courses: &[(String, String)]) -> bool {
+  timetable.courses.len() == courses.len()
+    && timetable.courses.iter().all(|course| {
+      courses
+        .iter()
+        .any(|(subject_name, code)| *subject_name == course.subject_name && *code == course.code)
+    })
+}
+
+fn timetable_grid(timetable: &Timetable, on_select: Callback<Course>) -> Html {
+  let hours = DAY_END_HOUR - DAY_START_HOUR;
+  let width = TIMES_WIDTH + DAY_WIDTH * DAYS.len() as u32;
+  let height = HEADER_HEIGHT + (hours as f32 * 60.0 * MINUTE_HEIGHT) as u32;
+
+  html! {
+    <div
+      class="relative bg-white text-black"
+      style={format!("width: {width}px; height: {height}px;")}
+    >
+      { for (0..=hours).map(|hour| {
+        let top = HEADER_HEIGHT as f32 + (hour * 60) as f32 * MINUTE_HEIGHT;
+        html! {
+          <div
+            class="absolute left-0 w-full border-t border-gray-500 text-sm"
+            style={format!("top: {top}px;")}
+          >
+            { format!("{}:00", DAY_START_HOUR + hour) }
+          </div>
+        }
+      }) }
+      { for DAYS.iter().enumerate().map(|(i, day)| {
+        let left = TIMES_WIDTH + i as u32 * DAY_WIDTH;
+        html! {
+          <div
+            class="absolute top-0 h-full border-l-4 border-gray-700 text-center"
+            style={format!("left: {left}px; width: {DAY_WIDTH}px;")}
+          >
+            { day.to_string() }
+          </div>
+        }
+      }) }
+      { for timetable.courses.iter().map(|course| course_block(course, on_select.clone())) }
+    </div>
+  }
+}
+
+fn course_block(course: &Course, on_select: Callback<Course>) -> Html {
+  let occ = &course.occurrence;
+  // TODO: temporary, remove when theres a struct for new courses with no timetable info
+  if occ.start_time == NaiveTime::MIN {
+    return html! {};
+  }
+
+  let day_start = NaiveTime::from_hms_opt(DAY_START_HOUR, 0, 0).unwrap();
+  let left = TIMES_WIDTH + (occ.weekday.number_from_monday() - 1) * DAY_WIDTH + 2;
+  let top =
+    HEADER_HEIGHT as f32 + (occ.start_time - day_start).num_minutes() as f32 * MINUTE_HEIGHT;
+  let height = occ.duration().num_minutes() as f32 * MINUTE_HEIGHT;
+
+  let [red, green, blue] = course.color();
+  let average_color = (red as u16 + green as u16 + blue as u16) / 3;
+  let foreground = if average_color < 128 {
+    "white"
+  } else {
+    "black"
+  };
+
+  let onclick = {
+    let course = course.clone();
+    move |_| on_select.emit(course.clone())
+  };
+
+  html! {
+    <button
+      class="absolute p-1 text-left text-sm overflow-hidden"
+      style={format!(
+        "left: {left}px; top: {top}px; width: {}px; height: {height}px; \
+        background-color: rgb({red}, {green}, {blue}); color: {foreground};",
+        DAY_WIDTH - 4,
+      )}
+      title={course.subject_name.clone()}
+      {onclick}
+    >
+      { &course.code }
+    </button>
+  }
+}
+
+fn course_details(course: &Course) -> Html {
+  let weeks = match &course.occurrence.weeks {
+    Some(weeks) => format!("{weeks:?}"),
+    None => "Unknown".to_string(),
+  };
+  let rows = [
+    ("Subject", course.subject_name.clone()),
+    ("Code", course.code.clone()),
+    ("Type", course.course_type.to_string()),
+    ("Occurrence", course.occurrence.to_string()),
+    ("Weeks", weeks),
+    ("Location", course.location.clone()),
+    ("Teacher", course.teacher.clone()),
+    ("Language", course.language.clone()),
+    ("Site", course.site.clone()),
+    ("Comment", course.comment.clone()),
+    ("Description", course.description.clone()),
+  ];
+
+  html! {
+    <table>
+      { for rows.into_iter().map(|(name, value)| html! {
+        <tr>
+          <th class="pr-2 text-left">{ name }</th>
+          <td>{ value }</td>
+        </tr>
+      }) }
+    </table>
+  }
+}
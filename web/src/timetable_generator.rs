@@ -1,26 +1,266 @@
-use timetable_optimizer_lib::data::Subject;
+use std::{cell::RefCell, rc::Rc};
+
+use gloo::worker::Spawnable;
+use timetable_optimizer_lib::{
+  bottleneck::{Conflicts, Unlock},
+  data::Subject,
+  generator::TimetableCount,
+  stats::TimetableStats,
+};
 use yew::prelude::*;
 
+use crate::{
+  generator_worker::{GeneratorInput, GeneratorOutput, GeneratorWorker},
+  statistics::StatisticsComponent,
+  timetable::TimetableViewerComponent,
+};
+
+/// Combination indices of the found timetables, appended chunk by chunk.
+/// The states of a job share the buffer, each sees the indices found until it, so a chunk doesn't
+/// copy the indices found before.
+#[derive(Clone, Default)]
+pub struct FoundTimetables {
+  indices: Rc<RefCell<Vec<u64>>>,
+  len: usize,
+}
+
+impl FoundTimetables {
+  fn extend(&self, indices: Vec<u64>) -> FoundTimetables {
+    let mut all_indices = self.indices.borrow_mut();
+    all_indices.extend(indices);
+    FoundTimetables {
+      indices: self.indices.clone(),
+      len: all_indices.len(),
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  pub fn get(&self, position: usize) -> Option<u64> {
+    (position < self.len).then(|| self.indices.borrow()[position])
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+    (0..self.len).map(|position| self.indices.borrow()[position])
+  }
+}
+
+impl PartialEq for FoundTimetables {
+  fn eq(&self, other: &FoundTimetables) -> bool {
+    Rc::ptr_eq(&self.indices, &other.indices) && self.len == other.len
+  }
+}
+
+#[derive(Default, PartialEq)]
+pub struct GenerationState {
+  pub job: u32,
+  pub checked: u64,
+  pub total: u64,
+  /// Timetables without clashes found so far.
+  pub valid: u64,
+  /// Timetables without clashes, counted before they are generated.
+  pub valid_count: Option<TimetableCount>,
+  /// Timetables without clashes left after each filter updating the courses, with the filter's index.
+  pub course_funnel: Vec<(usize, TimetableCount)>,
+  /// Timetables left after each filter.
+  pub funnel: Vec<u64>,
+  pub timetables: FoundTimetables,
+  pub stats: TimetableStats,
+  /// Searched only when there are few timetables.
+  pub bottlenecks: Option<(Conflicts, Vec<Unlock>)>,
+  pub error: Option<String>,
+}
+
+pub enum GenerationAction {
+  Restart(u32),
+  Output(GeneratorOutput),
+}
+
+impl Reducible for GenerationState {
+  type Action = GenerationAction;
+
+  fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+    match action {
+      GenerationAction::Restart(job) => GenerationState {
+        job,
+        ..Default::default()
+      }
+      .into(),
+      GenerationAction::Output(GeneratorOutput::Progress {
+        job,
+        checked,
+        total,
+        valid,
+        funnel,
+        timetables,
+        stats,
+      }) if job == self.job => {
+        let all_funnel = if self.funnel.is_empty() {
+          funnel
+        } else {
+          self.funnel.iter().zip(funnel).map(|(a, b)| a + b).collect()
+        };
+        GenerationState {
+          job,
+          checked,
+          total,
+          valid: self.valid + valid,
+          funnel: all_funnel,
+          valid_count: self.valid_count,
+          course_funnel: self.course_funnel.clone(),
+          timetables: self.timetables.extend(timetables),
+          stats: *stats,
+          bottlenecks: self.bottlenecks.clone(),
+          error: None,
+        }
+        .into()
+      }
+      GenerationAction::Output(GeneratorOutput::Count {
+        job,
+        count,
+        course_funnel,
+      }) if job == self.job => GenerationState {
+        job,
+        valid_count: Some(count),
+        course_funnel,
+        ..Default::default()
+      }
+      .into(),
+      GenerationAction::Output(GeneratorOutput::Bottlenecks {
+        job,
+        conflicts,
+        unlocks,
+      }) if job == self.job => GenerationState {
+        job,
+        valid_count: self.valid_count,
+        course_funnel: self.course_funnel.clone(),
+        bottlenecks: Some((conflicts, unlocks)),
+        ..Default::default()
+      }
+      .into(),
+      GenerationAction::Output(GeneratorOutput::Error { job, message }) if job == self.job => {
+        GenerationState {
+          job,
+          error: Some(message),
+          ..Default::default()
+        }
+        .into()
+      }
+      // outputs of a previous job
+      GenerationAction::Output(_) => self,
+    }
+  }
+}
+
 #[derive(Properties, PartialEq)]
 pub struct TimetableGeneratorProps {
   pub subjects: Vec<Subject>,
+  pub filters: Vec<String>,
+  pub shared_courses: Option<Vec<(String, String)>>,
 }
 
 #[function_component(TimetableGenerator)]
 pub fn timetable_generator(props: &TimetableGeneratorProps) -> Html {
-  let on_restart_agent = {
+  let state = use_reducer(GenerationState::default);
+  let next_job = use_mut_ref(|| 0);
+  // the subjects and filters of the current job, the found indices are only valid for them
+  let job_inputs = use_mut_ref(|| (Vec::<Subject>::new(), Vec::<String>::new()));
+
+  let bridge = {
+    let state = state.dispatcher();
+    use_memo((), move |_| {
+      GeneratorWorker::spawner()
+        .callback(move |output| state.dispatch(GenerationAction::Output(output)))
+        .spawn_with_loader("/worker_loader.js")
+    })
+  };
+
+  let restart = {
+    let state = state.dispatcher();
+    let bridge = bridge.clone();
+    let job_inputs = job_inputs.clone();
+    move |subjects: Vec<Subject>, filters: Vec<String>| {
+      *job_inputs.borrow_mut() = (subjects.clone(), filters.clone());
+      let job = {
+        let mut next_job = next_job.borrow_mut();
+        *next_job += 1;
+        *next_job
+      };
+      state.dispatch(GenerationAction::Restart(job));
+      bridge.send(GeneratorInput {
+        job,
+        subjects,
+        filters,
+      });
+    }
+  };
+
+  {
+    let restart = restart.clone();
+    use_effect_with(
+      (props.subjects.clone(), props.filters.clone()),
+      move |(subjects, filters)| {
+        restart(subjects.clone(), filters.clone());
+      },
+    );
+  }
+
+  let on_restart = {
     let subjects = props.subjects.clone();
-    move |_| {
-      gloo::console::log!(format!("Restarting agent, there are {} subjects", subjects.len()));
+    let filters = props.filters.clone();
+    move |_| restart(subjects.clone(), filters.clone())
+  };
+
+  // the subjects change a render before the job is restarted, the old indices are left out until then
+  let timetables = {
+    let (subjects, filters) = &*job_inputs.borrow();
+    if *subjects == props.subjects && *filters == props.filters {
+      state.timetables.clone()
+    } else {
+      FoundTimetables::default()
     }
   };
 
+  let percentage = match state.total {
+    0 => 100,
+    total => state.checked * 100 / total,
+  };
+
   html! {
     <div>
-      { "Timetable Generator" }
-      <button onclick={on_restart_agent.clone()}>
-        { "Restart agent" }
+      <h1>{ "Timetable Generator" }</h1>
+      if let Some(error) = &state.error {
+        <p>{ format!("Failed to generate timetables: {error}") }</p>
+      } else {
+        <progress value={state.checked.to_string()} max={state.total.max(1).to_string()} />
+        <p>{ format!("Checked {} of {} combinations ({percentage}%)", state.checked, state.total) }</p>
+        <p>{ format!("Timetables matching the filters: {}", state.timetables.len()) }</p>
+      }
+      <button onclick={on_restart}>
+        { "Restart" }
       </button>
+      <StatisticsComponent
+        subjects={props.subjects.clone()}
+        filters={props.filters.clone()}
+        valid={state.valid}
+        valid_count={state.valid_count}
+        course_funnel={state.course_funnel.clone()}
+        funnel={state.funnel.clone()}
+        stats={state.stats.clone()}
+        bottlenecks={state.bottlenecks.clone()}
+      />
+      <TimetableViewerComponent
+        subjects={props.subjects.clone()}
+        filters={props.filters.clone()}
+        {timetables}
+        shared_courses={props.shared_courses.clone()}
+      />
     </div>
   }
 }
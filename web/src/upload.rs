@@ -5,6 +5,52 @@ use timetable_optimizer_lib::{data::Subject, excel_parser};
 use web_sys::HtmlInputElement;
 use yew::prelude::*;
 
+#[derive(Clone, PartialEq)]
+enum FileStatus {
+  Queued,
+  Parsing,
+  Ok,
+  Failed(String),
+}
+
+#[derive(Default, PartialEq)]
+struct UploadState {
+  /// The files selected last, the statuses of the files selected before are ignored.
+  batch: u32,
+  /// File names and their statuses in the order they were selected.
+  files: Vec<(String, FileStatus)>,
+}
+
+enum UploadAction {
+  Start(u32, Vec<String>),
+  SetStatus(u32, usize, FileStatus),
+}
+
+impl Reducible for UploadState {
+  type Action = UploadAction;
+
+  fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+    match action {
+      UploadAction::Start(batch, file_names) => UploadState {
+        batch,
+        files: file_names
+          .into_iter()
+          .map(|name| (name, FileStatus::Queued))
+          .collect(),
+      }
+      .into(),
+      UploadAction::SetStatus(batch, index, status) if batch == self.batch => {
+        let mut files = self.files.clone();
+        if let Some((_name, file_status)) = files.get_mut(index) {
+          *file_status = status;
+        }
+        UploadState { batch, files }.into()
+      }
+      UploadAction::SetStatus(..) => self,
+    }
+  }
+}
+
 #[derive(Properties, PartialEq)]
 pub struct UploadProps {
   pub on_files_processed: Callback<Vec<Subject>>,
@@ -14,26 +60,44 @@ pub struct UploadProps {
 pub fn upload_component(props: &UploadProps) -> Html {
   let readers = use_state(Vec::new);
   let processed_files = use_state(Vec::new);
-  let queued_files = use_state(Vec::new);
+  // the files are processed from the end, the index is the position in the upload state
+  let queued_files = use_state(Vec::<(usize, gloo::file::File)>::new);
+  let state = use_reducer(UploadState::default);
+  let batch = use_mut_ref(|| 0);
 
   let on_file_change = {
     let queued_files = queued_files.clone();
+    let processed_files = processed_files.clone();
+    let readers = readers.clone();
+    let state = state.dispatcher();
+    let batch = batch.clone();
     Callback::from(move |e: Event| {
       let input: HtmlInputElement = e.target_unchecked_into();
-      let files = input.files().unwrap();
-      queued_files.set(
-        js_sys::try_iter(&files)
-          .unwrap()
-          .unwrap()
-          .map(|file| file.unwrap())
-          .map(web_sys::File::from)
-          .map(gloo::file::File::from)
-          .collect(),
-      );
+      let Some(files) = input.files() else {
+        return;
+      };
+      let files = (0..files.length())
+        .filter_map(|i| files.get(i))
+        .map(gloo::file::File::from)
+        .collect::<Vec<_>>();
+      let current_batch = {
+        let mut batch = batch.borrow_mut();
+        *batch += 1;
+        *batch
+      };
+      state.dispatch(UploadAction::Start(
+        current_batch,
+        files.iter().map(|file| file.name()).collect(),
+      ));
+      // dropping the readers of the previous files stops reading them
+      readers.set(Vec::new());
+      processed_files.set(Vec::new());
+      queued_files.set(files.into_iter().enumerate().collect());
     })
   };
 
   let on_files_processed = props.on_files_processed.clone();
+  let dispatcher = state.dispatcher();
   use_effect_with(queued_files, move |queued_files| {
     let queued_files = queued_files.clone();
     let mut new_queued_files = (*queued_files).clone();
@@ -41,21 +105,43 @@ pub fn upload_component(props: &UploadProps) -> Html {
 
     match queued_file {
       None => {
-        if processed_files.len() > 0 {
+        // the subjects of the files that failed are left out
+        if !processed_files.is_empty() {
           on_files_processed.emit((*processed_files).clone());
           processed_files.set(Vec::new());
           readers.set(Vec::new());
         }
       }
-      Some(queued_file) => {
+      Some((index, queued_file)) => {
+        let current_batch = *batch.borrow();
+        dispatcher.dispatch(UploadAction::SetStatus(
+          current_batch,
+          index,
+          FileStatus::Parsing,
+        ));
         let file_name = queued_file.name();
         let reader = Rc::new(gloo::file::callbacks::read_as_bytes(
           &queued_file,
           move |bytes| {
-            match bytes {
-              Err(err) => gloo::dialogs::alert(&err.to_string()),
-              Ok(bytes) => {
-                let subject = parse_subject(file_name, bytes);
+            // a file read before other files were selected
+            if *batch.borrow() != current_batch {
+              return;
+            }
+            let subject = bytes
+              .map_err(|err| err.to_string())
+              .and_then(|bytes| parse_subject(file_name, bytes));
+            match subject {
+              Err(err) => dispatcher.dispatch(UploadAction::SetStatus(
+                current_batch,
+                index,
+                FileStatus::Failed(err),
+              )),
+              Ok(subject) => {
+                dispatcher.dispatch(UploadAction::SetStatus(
+                  current_batch,
+                  index,
+                  FileStatus::Ok,
+                ));
                 processed_files.set({
                   let mut processed_files = (*processed_files).clone();
                   processed_files.push(subject);
@@ -78,12 +164,28 @@ pub fn upload_component(props: &UploadProps) -> Html {
   html! {
     <div>
       <input type="file" multiple=true onchange={on_file_change} />
+      <ul>
+        { for state.files.iter().map(|(name, status)| html! {
+          <li>{ format!("{name}: {}", status_text(status)) }</li>
+        }) }
+      </ul>
     </div>
   }
 }
 
-fn parse_subject(file_name: String, bytes: Vec<u8>) -> Subject {
+fn status_text(status: &FileStatus) -> String {
+  match status {
+    FileStatus::Queued => "queued".to_string(),
+    FileStatus::Parsing => "parsing".to_string(),
+    FileStatus::Ok => "ok".to_string(),
+    FileStatus::Failed(reason) => format!("failed, {reason}"),
+  }
+}
+
+fn parse_subject(file_name: String, bytes: Vec<u8>) -> Result<Subject, String> {
   let cursor = Cursor::new(bytes);
-  let mut excel: Xlsx<_> = calamine::open_workbook_from_rs(cursor).unwrap();
-  excel_parser::parse_subject(file_name, &mut excel)
+  let mut excel: Xlsx<_> =
+    calamine::open_workbook_from_rs(cursor).map_err(|err| format!("not an excel file ({err})"))?;
+  let subject_name = excel_parser::subject_name(&file_name, &mut excel);
+  excel_parser::parse_subject(subject_name, &mut excel)
 }